@@ -1,14 +1,27 @@
-use std::ops::{Index, IndexMut, Not};
+use std::{
+    cmp::Reverse,
+    ops::{Index, IndexMut, Not},
+};
 
 use rand::{seq::SliceRandom, Rng};
 use smallvec::{smallvec, SmallVec};
 use smartstring::alias::String;
 
-mod loading;
+mod id;
+mod list;
+pub(crate) mod loading;
+pub(crate) mod matching;
+mod optional;
+mod pattern;
 mod saving;
 
+pub use id::CardId;
+pub use list::{ListAnswer, ListCredit};
+pub use optional::OptionalAnswer;
+pub use pattern::AnswerPattern;
+
 /// A side of a flashcard.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Side {
     Front,
     Back,
@@ -29,12 +42,20 @@ impl Not for Side {
 /// [`McCard`].
 ///
 /// Stores multiple variants of text in order to show multiple variants of the
-/// same question and accept multiple answers.
+/// same question and accept multiple answers.  Variants may also be
+/// [`AnswerPattern`]s, which are shown as their canonical text,
+/// [`ListAnswer`]s, whose items can be given in any order, or
+/// [`OptionalAnswer`]s, which have parts that can be left out.  Patterns come
+/// after all the plain text variants, lists come after the patterns, and
+/// answers with optional parts come after the lists.
 ///
 /// In the future may include images.
 #[derive(Debug, Clone, PartialEq)]
 pub struct CardSide {
     text: SmallVec<[String; 1]>,
+    patterns: SmallVec<[AnswerPattern; 0]>,
+    lists: SmallVec<[ListAnswer; 0]>,
+    optional: SmallVec<[OptionalAnswer; 0]>,
 }
 
 impl CardSide {
@@ -42,6 +63,9 @@ impl CardSide {
     pub fn empty() -> Self {
         Self {
             text: SmallVec::new(),
+            patterns: SmallVec::new(),
+            lists: SmallVec::new(),
+            optional: SmallVec::new(),
         }
     }
 
@@ -49,6 +73,9 @@ impl CardSide {
     pub fn new(text: impl Into<String>) -> Self {
         Self {
             text: smallvec![text.into()],
+            patterns: SmallVec::new(),
+            lists: SmallVec::new(),
+            optional: SmallVec::new(),
         }
     }
 
@@ -56,6 +83,9 @@ impl CardSide {
     pub fn new_multi<S: Into<String>>(texts: impl IntoIterator<Item = S>) -> Self {
         Self {
             text: texts.into_iter().map(Into::into).collect(),
+            patterns: SmallVec::new(),
+            lists: SmallVec::new(),
+            optional: SmallVec::new(),
         }
     }
 
@@ -64,6 +94,36 @@ impl CardSide {
         self.text.push(text.into());
     }
 
+    /// Add a new pattern answers can match to this.
+    pub fn push_pattern(&mut self, pattern: AnswerPattern) {
+        self.patterns.push(pattern);
+    }
+
+    /// Returns an iterator over the patterns in this.
+    pub fn patterns(&self) -> impl ExactSizeIterator<Item = &AnswerPattern> {
+        self.patterns.iter()
+    }
+
+    /// Add a new list answers can give the items of in any order to this.
+    pub fn push_list(&mut self, list: ListAnswer) {
+        self.lists.push(list);
+    }
+
+    /// Returns an iterator over the lists in this.
+    pub fn lists(&self) -> impl ExactSizeIterator<Item = &ListAnswer> {
+        self.lists.iter()
+    }
+
+    /// Add a new answer with optional parts to this.
+    pub fn push_optional(&mut self, answer: OptionalAnswer) {
+        self.optional.push(answer);
+    }
+
+    /// Returns an iterator over the answers with optional parts in this.
+    pub fn optional(&self) -> impl ExactSizeIterator<Item = &OptionalAnswer> {
+        self.optional.iter()
+    }
+
     /// Remove and return the element at position `index`, shifting all elements
     /// after it to the left.
     ///
@@ -74,27 +134,115 @@ impl CardSide {
         self.text.remove(index)
     }
 
-    /// Returns a reference to the text at the index.
+    /// Returns a reference to the text at the index, or the canonical text of
+    /// a pattern or the text of a list or an answer with optional parts if the
+    /// index is past the end of the plain text.
     pub fn get_text(&self, index: usize) -> Option<&str> {
-        self.text.get(index).map(AsRef::as_ref)
+        self.iter_text().nth(index)
     }
 
-    /// Returns a mutable reference to the text at the index.
+    /// Returns a mutable reference to the plain text at the index.
     pub fn get_text_mut(&mut self, index: usize) -> Option<&mut String> {
         self.text.get_mut(index)
     }
 
+    /// Returns an iterator over all the text in this, followed by the
+    /// canonical text of all the patterns, the text of all the lists, and the
+    /// text of all the answers with optional parts.
+    pub fn iter_text(&self) -> impl Iterator<Item = &str> {
+        self.text
+            .iter()
+            .map(String::as_str)
+            .chain(self.patterns.iter().map(AnswerPattern::canonical))
+            .chain(self.lists.iter().map(ListAnswer::text))
+            .chain(self.optional.iter().map(OptionalAnswer::text))
+    }
+
+    /// Returns each text in [`CardSide::iter_text`] with its index, but with
+    /// answers with optional parts replaced by every way of writing them.
+    pub(crate) fn iter_expansions(&self) -> impl Iterator<Item = (usize, &str)> {
+        let plain = self.text.len() + self.patterns.len() + self.lists.len();
+        self.iter_text()
+            .take(plain)
+            .enumerate()
+            .chain(
+                self.optional
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(index, answer)| {
+                        answer.expansions().map(move |text| (plain + index, text))
+                    }),
+            )
+    }
+
     /// Returns a random piece text from this to use as a question or answer.
     pub fn any_text<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&str> {
-        self.text.choose(rng).map(String::as_str)
+        self.iter_text()
+            .collect::<SmallVec<[_; 4]>>()
+            .choose(rng)
+            .copied()
     }
 
     /// Returns true if the provided text matches any of the text in this by the
     /// rules provided.
     pub fn matches_text(&self, rules: &RecallSettings, text: &str) -> bool {
-        self.text
+        self.match_text(rules, text).is_some()
+    }
+
+    /// Returns the index of the text in this the provided text matches and
+    /// how lenient the rules provided had to be for it to match, or `None` if
+    /// it doesn't match any.
+    ///
+    /// Lists only match if the text gives enough of their items and nothing
+    /// else; see [`CardSide::list_credit`] for partial matches.
+    ///
+    /// Prefers text that matches exactly, then text that matches with the
+    /// fewest typos.
+    pub fn match_text(&self, rules: &RecallSettings, text: &str) -> Option<(usize, Leniency)> {
+        let matches = self
+            .text
+            .iter()
+            .map(|template| rules.test_match(template, text))
+            .chain(
+                self.patterns
+                    .iter()
+                    .map(|pattern| rules.test_pattern(pattern, text)),
+            )
+            .chain(self.lists.iter().map(|list| {
+                let (credit, leniency) = rules.test_list(list, text);
+                credit.is_complete().then_some(leniency)
+            }))
+            .chain(
+                self.optional
+                    .iter()
+                    .map(|answer| rules.test_optional(answer, text)),
+            );
+
+        matches
+            .enumerate()
+            .filter_map(|(index, leniency)| Some((index, leniency?)))
+            .min_by_key(|(_, leniency)| leniency.preference())
+    }
+
+    /// Returns the index of the list in this the provided text gives the most
+    /// items of, along with how much of that list it gives, or `None` if it
+    /// gives no items of any list.
+    ///
+    /// The index counts all the variants of this, as in
+    /// [`CardSide::match_text`].
+    pub fn list_credit(
+        &self,
+        rules: &RecallSettings,
+        text: &str,
+    ) -> Option<(usize, ListCredit<'_>)> {
+        let first = self.text.len() + self.patterns.len();
+        self.lists
             .iter()
-            .any(|template| rules.test_match(template, text))
+            .map(|list| rules.test_list(list, text).0)
+            .enumerate()
+            .filter(|(_, credit)| credit.given > 0)
+            .max_by_key(|(_, credit)| (credit.given.min(credit.required), Reverse(credit.wrong)))
+            .map(|(index, credit)| (first + index, credit))
     }
 }
 
@@ -147,6 +295,19 @@ impl Decoys {
         self.text.choose_multiple(rng, count).map(AsRef::as_ref)
     }
 
+    /// Returns an iterator over all the decoys in this.
+    pub(crate) fn iter_text(&self) -> impl Iterator<Item = &str> {
+        self.text.iter().map(AsRef::as_ref)
+    }
+
+    /// Returns true if the provided text matches any of the decoys in this by
+    /// the rules provided.
+    pub fn matches_text(&self, rules: &RecallSettings, text: &str) -> bool {
+        self.text
+            .iter()
+            .any(|decoy| rules.test_match(decoy, text).is_some())
+    }
+
     /// Returns the number of text decoys this has.
     pub fn text_count(&self) -> usize {
         self.text.len()
@@ -166,6 +327,12 @@ impl<S: Into<String>> FromIterator<S> for Decoys {
 pub struct Flashcard {
     pub front: CardSide,
     pub back: CardSide,
+    /// Topics the card is about, such as "capitals".  Used to pick similar
+    /// decoys.
+    pub tags: Vec<String>,
+    /// What the card's [`CardId`] is made from instead of its first front
+    /// text, so it can be kept when that text changes.
+    pub id_text: Option<String>,
 }
 
 impl Flashcard {
@@ -174,6 +341,8 @@ impl Flashcard {
         Self {
             front: CardSide::empty(),
             back: CardSide::empty(),
+            tags: Vec::new(),
+            id_text: None,
         }
     }
 
@@ -182,6 +351,8 @@ impl Flashcard {
         Self {
             front: front.into().into(),
             back: back.into().into(),
+            tags: Vec::new(),
+            id_text: None,
         }
     }
 }
@@ -212,6 +383,9 @@ pub struct McCard {
     pub question: CardSide,
     pub answer: CardSide,
     pub decoys: Decoys,
+    /// What the card's [`CardId`] is made from instead of its first question
+    /// text, so it can be kept when that text changes.
+    pub id_text: Option<String>,
 }
 
 impl McCard {
@@ -222,6 +396,7 @@ impl McCard {
             question: CardSide::empty(),
             answer: CardSide::empty(),
             decoys: Decoys::empty(),
+            id_text: None,
         }
     }
 }
@@ -278,6 +453,7 @@ impl Set {
                 question: CardSide::new(question),
                 answer: CardSide::new(answer),
                 decoys: decoys.into_iter().collect(),
+                id_text: None,
             }
         }
 
@@ -314,18 +490,38 @@ pub struct RecallSettings {
     pub typ: RecallType,
     /// Does capitalization in the answer matter?
     pub check_caps: bool,
-}
-
-impl RecallSettings {
-    fn test_match(&self, a: &str, b: &str) -> bool {
-        let a = a.trim();
-        let b = b.trim();
-        if self.check_caps {
-            a == b
-        } else {
-            unicase::eq(a, b)
-        }
-    }
+    /// Do accents and other diacritics in the answer matter?
+    pub check_accents: bool,
+    /// Should runs of whitespace inside the answer be treated as one space?
+    pub collapse_whitespace: bool,
+    /// What punctuation in the answer doesn't matter?
+    pub ignore_punctuation: Punctuation,
+    /// How many typos can the answer have and still be accepted?
+    pub allowed_typos: Typos,
+    /// What language is text on this side written in?  Used to pick the right
+    /// rules when capitalization doesn't matter.
+    pub language: Option<Language>,
+    /// How should text be normalized before being compared?
+    pub normalization: Normalization,
+    /// What separates the items of a [`ListAnswer`]?
+    pub list_separator: char,
+    /// Leading words such as "to" or "der" that are handled separately from
+    /// the rest of the answer.
+    pub ignore_prefixes: Vec<String>,
+    /// How are [`RecallSettings::ignore_prefixes`] handled?
+    pub prefix_mode: PrefixMode,
+    /// How are decoys for multiple choice questions picked?
+    pub decoys: DecoyMode,
+    /// Should multiple choice cards without enough decoys use the answers to
+    /// other multiple choice cards as decoys?
+    pub borrow_decoys: bool,
+    /// Percent chance that a multiple choice question gets a "None of the
+    /// above" choice.  Only used for multiple choice cards.
+    pub none_of_the_above: u32,
+    /// Percent chance that a multiple choice question about a card with more
+    /// than one answer gets an "All of the above" choice.  Only used for
+    /// multiple choice cards.
+    pub all_of_the_above: u32,
 }
 
 impl Default for RecallSettings {
@@ -333,12 +529,151 @@ impl Default for RecallSettings {
         Self {
             typ: RecallType::Mc,
             check_caps: false,
+            check_accents: true,
+            collapse_whitespace: false,
+            ignore_punctuation: Punctuation::None,
+            allowed_typos: Typos::Count(0),
+            language: None,
+            normalization: Normalization::Nfc,
+            list_separator: ',',
+            ignore_prefixes: Vec::new(),
+            prefix_mode: PrefixMode::Optional,
+            decoys: DecoyMode::Random,
+            borrow_decoys: false,
+            none_of_the_above: 0,
+            all_of_the_above: 0,
         }
     }
 }
 
-/// How much of a side of a card does the player need to recall?
+/// Which of the rules in [`RecallSettings`] had to be relaxed for an answer to
+/// be accepted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Leniency {
+    /// Capitalization was wrong.
+    pub caps: bool,
+    /// Accents or other diacritics were missing or wrong.
+    pub accents: bool,
+    /// Whitespace inside the answer was collapsed.
+    pub whitespace: bool,
+    /// Punctuation was missing or wrong.
+    pub punctuation: bool,
+    /// A leading word in [`RecallSettings::ignore_prefixes`] was missing,
+    /// added, or different.
+    pub prefix: bool,
+    /// How many letters were inserted, deleted, replaced, or swapped.
+    pub typos: usize,
+}
+
+impl Leniency {
+    /// Whether the answer was accepted without relaxing any rules.
+    pub fn is_exact(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Sort key putting the matches to prefer first: exact ones, then ones with
+    /// the fewest typos.
+    pub(crate) fn preference(&self) -> (bool, usize) {
+        (!self.is_exact(), self.typos)
+    }
+
+    /// Leniency needed to accept two parts of the same answer.
+    pub(crate) fn combine(self, other: Self) -> Self {
+        Self {
+            caps: self.caps || other.caps,
+            accents: self.accents || other.accents,
+            whitespace: self.whitespace || other.whitespace,
+            punctuation: self.punctuation || other.punctuation,
+            prefix: self.prefix || other.prefix,
+            typos: self.typos + other.typos,
+        }
+    }
+}
+
+/// A language tag such as "en", "tr", or "de-AT".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    tag: String,
+}
+
+impl Language {
+    /// A `Language` with the given tag.
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self { tag: tag.into() }
+    }
+
+    /// The tag this was created from.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The primary language subtag ("de" for "de-AT").
+    fn primary(&self) -> &str {
+        self.tag.split(['-', '_']).next().unwrap_or_default()
+    }
+}
+
+/// Punctuation ignored when comparing text.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Punctuation {
+    /// All punctuation matters.
+    None,
+    /// ASCII punctuation and common non-ASCII punctuation such as "¿" and "«"
+    /// are ignored.
+    All,
+    /// Only the characters in this string are ignored.
+    Only(String),
+}
+
+/// How many typos an answer may have.
+///
+/// A typo is an inserted, deleted, or replaced letter or a swapped pair of
+/// letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Typos {
+    /// A fixed number of typos.
+    Count(usize),
+    /// A percentage of the length of the correct answer, rounded down.
+    Percent(u32),
+}
+
+/// How leading words in [`RecallSettings::ignore_prefixes`] are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixMode {
+    /// The word may be left out or replaced by any other prefix, so "run" and
+    /// "to run" both match "to run".
+    Optional,
+    /// The word must be given and match the one on the card without typos,
+    /// so "die Hund" does not match "der Hund".  Other leniency only applies
+    /// to the rest of the answer.
+    Strict,
+}
+
+/// How decoys for multiple choice questions are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecoyMode {
+    /// Any decoy is equally likely.
+    Random,
+    /// Decoys most similar to the correct answer are picked, judged by their
+    /// length, the characters they share, the tags their cards share, and
+    /// whether they are written in the same script or are both numbers.
+    Similar,
+}
+
+/// Unicode normalization form used when comparing text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Canonical composition; "é" typed as one character or as "e" and a
+    /// combining accent are the same.
+    Nfc,
+    /// Compatibility composition; like [`Normalization::Nfc`] but also treats
+    /// characters such as "ﬁ" and "fi" or "²" and "2" as the same.
+    Nfkc,
+}
+
+/// How much of a side of a card does the player need to recall?
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum RecallType {
     /// Does not need to recall.
@@ -432,14 +767,17 @@ D: 3decoy2
             RecallSettings {
                 typ: RecallType::Mc,
                 check_caps: true,
+                ..Default::default()
             },
             RecallSettings {
                 typ: RecallType::None,
                 check_caps: false,
+                ..Default::default()
             },
             RecallSettings {
                 typ: RecallType::Mc,
                 check_caps: false,
+                ..Default::default()
             },
         )
         .save_to_writer(&mut buf)
@@ -456,14 +794,17 @@ D: 3decoy2
                 RecallSettings {
                     typ: RecallType::Text,
                     check_caps: true,
+                    ..Default::default()
                 },
                 RecallSettings {
                     typ: RecallType::None,
                     check_caps: false,
+                    ..Default::default()
                 },
                 RecallSettings {
                     typ: RecallType::Mc,
                     check_caps: false,
+                    ..Default::default()
                 },
             )
         );
@@ -0,0 +1,139 @@
+use super::{CardSide, Flashcard, McCard};
+
+/// Identifies a card by its first front or question text, so the same card
+/// gets the same id every time a set is loaded, on every platform.
+///
+/// Editing other text on a card, such as fixing a typo in its answer or adding
+/// another answer, keeps its id.  Cards can be given an "ID:" line to keep
+/// their id when their first front or question text changes too, or to tell
+/// apart cards that start with the same text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CardId(u64);
+
+impl CardId {
+    /// The id with the given value.
+    pub fn from_u64(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// The value of this id.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// The id of a card of the kind `kind`, with `id_text` or else `text`.
+    fn new(kind: &[u8], id_text: Option<&str>, text: &CardSide) -> Self {
+        let mut hasher = Fnv::new();
+        hasher.write(kind);
+        match id_text {
+            Some(id_text) => {
+                hasher.write(b"#");
+                hasher.write_text(id_text);
+            }
+            None => {
+                hasher.write(b"=");
+                hasher.write_text(text.iter_text().next().unwrap_or_default());
+            }
+        }
+        Self(hasher.0)
+    }
+}
+
+impl Flashcard {
+    /// An id for this card based on [`Flashcard::id_text`], or else its first
+    /// front text.
+    pub fn id(&self) -> CardId {
+        CardId::new(b"F", self.id_text.as_deref(), &self.front)
+    }
+}
+
+impl McCard {
+    /// An id for this card based on [`McCard::id_text`], or else its first
+    /// question text.
+    pub fn id(&self) -> CardId {
+        CardId::new(b"Q", self.id_text.as_deref(), &self.question)
+    }
+}
+
+/// 64 bit FNV-1a.  Used instead of [`std::hash::Hasher`]s since their output
+/// may change between Rust versions.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Writes `text` followed by a byte that can't appear in UTF-8, so "ab", "c"
+    /// and "a", "bc" hash differently.
+    fn write_text(&mut self, text: &str) {
+        self.write(text.as_bytes());
+        self.write(&[0xff]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::card::Decoys;
+
+    use super::*;
+
+    #[test]
+    fn id_depends_on_first_text() {
+        let card = Flashcard::new("a", "0");
+        assert_eq!(card.id(), Flashcard::new("a", "1").id());
+        assert_ne!(card.id(), Flashcard::new("b", "0").id());
+        assert_ne!(card.id(), Flashcard::new("0", "a").id());
+
+        let mut edited = card.clone();
+        edited.front.push_text("A");
+        edited.back.push_text("zero");
+        assert_eq!(edited.id(), card.id());
+
+        let mc = McCard {
+            question: CardSide::new("a"),
+            answer: CardSide::new("0"),
+            decoys: Decoys::empty(),
+            id_text: None,
+        };
+        assert_ne!(mc.id(), card.id());
+    }
+
+    #[test]
+    fn id_text() {
+        let mut card = Flashcard::new("a", "0");
+        card.id_text = Some("first".into());
+        let id = card.id();
+        assert_ne!(id, Flashcard::new("a", "0").id());
+        card.front = CardSide::new("b");
+        assert_eq!(card.id(), id);
+        // Text can't be mistaken for an id.
+        assert_ne!(Flashcard::new("#first", "0").id(), id);
+    }
+
+    #[test]
+    fn id_stable() {
+        // Ids are saved with progress, so they must not change.
+        assert_eq!(Flashcard::new("a", "0").id(), CardId(0x405a_6083_7a6e_ed98));
+    }
+
+    #[test]
+    fn mc_id_ignores_decoys() {
+        let mut card = McCard {
+            question: CardSide::new("q"),
+            answer: CardSide::new("a"),
+            decoys: ["d"].into_iter().collect(),
+            id_text: None,
+        };
+        let id = card.id();
+        card.decoys.push_text("e");
+        assert_eq!(card.id(), id);
+    }
+}
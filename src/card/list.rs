@@ -0,0 +1,178 @@
+use smartstring::alias::String;
+
+use super::{Leniency, RecallSettings};
+
+/// An answer made of a list of items that can be given in any order, such as
+/// "red, blue, yellow".
+///
+/// Items are separated by [`RecallSettings::list_separator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListAnswer {
+    text: String,
+    required: Option<usize>,
+}
+
+impl ListAnswer {
+    /// A list where every item has to be given.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            required: None,
+        }
+    }
+
+    /// A list where only `required` of the items have to be given.
+    pub fn new_some(text: impl Into<String>, required: usize) -> Self {
+        Self {
+            text: text.into(),
+            required: Some(required),
+        }
+    }
+
+    /// The list as written, including separators.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// How many items have to be given, or `None` if all of them do.
+    pub fn required(&self) -> Option<usize> {
+        self.required
+    }
+
+    fn items(&self, separator: char) -> impl Iterator<Item = &str> {
+        split_items(&self.text, separator)
+    }
+
+    /// How many items this has when separated by `separator`.
+    pub(crate) fn item_count(&self, separator: char) -> usize {
+        self.items(separator).count()
+    }
+}
+
+/// How much of a [`ListAnswer`] an answer gave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListCredit<'a> {
+    /// How many items of the list were given.
+    pub given: usize,
+    /// How many items of the list have to be given.
+    pub required: usize,
+    /// Items of the list that weren't given.
+    pub missing: Vec<&'a str>,
+    /// How many of the items in the answer aren't in the list (or are repeats).
+    pub wrong: usize,
+}
+
+impl<'a> ListCredit<'a> {
+    /// Whether enough items were given and none were wrong.
+    pub fn is_complete(&self) -> bool {
+        self.given >= self.required && self.wrong == 0
+    }
+}
+
+impl RecallSettings {
+    /// Compares each item in `text` to the items in `list`, returning how much
+    /// of the list was given and how lenient these rules had to be for the
+    /// given items to match.
+    pub(crate) fn test_list<'a>(
+        &self,
+        list: &'a ListAnswer,
+        text: &str,
+    ) -> (ListCredit<'a>, Leniency) {
+        let mut remaining = list.items(self.list_separator).collect::<Vec<_>>();
+        let required = list
+            .required
+            .unwrap_or(remaining.len())
+            .min(remaining.len());
+        let mut leniency = Leniency::default();
+        let mut given = 0;
+        let mut wrong = 0;
+        for item in split_items(text, self.list_separator) {
+            let best = remaining
+                .iter()
+                .enumerate()
+                .filter_map(|(index, template)| Some((index, self.test_match(template, item)?)))
+                .min_by_key(|(_, leniency)| leniency.preference());
+            match best {
+                Some((index, item_leniency)) => {
+                    remaining.remove(index);
+                    given += 1;
+                    leniency = leniency.combine(item_leniency);
+                }
+                None => wrong += 1,
+            }
+        }
+        let credit = ListCredit {
+            given,
+            required,
+            missing: remaining,
+            wrong,
+        };
+        (credit, leniency)
+    }
+}
+
+/// Splits `text` at `separator`, skipping blank items.
+fn split_items(text: &str, separator: char) -> impl Iterator<Item = &str> {
+    text.split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_any_order() {
+        let rules = RecallSettings::default();
+        let list = ListAnswer::new("red, blue, yellow");
+        let (credit, leniency) = rules.test_list(&list, "yellow,red , Blue");
+        assert!(credit.is_complete());
+        assert_eq!(credit.given, 3);
+        assert_eq!(credit.required, 3);
+        assert_eq!(
+            leniency,
+            Leniency {
+                caps: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn list_missing_and_wrong() {
+        let rules = RecallSettings::default();
+        let list = ListAnswer::new("red, blue, yellow");
+        let (credit, _) = rules.test_list(&list, "blue, green, blue");
+        assert_eq!(
+            credit,
+            ListCredit {
+                given: 1,
+                required: 3,
+                missing: vec!["red", "yellow"],
+                wrong: 2,
+            }
+        );
+        assert!(!credit.is_complete());
+    }
+
+    #[test]
+    fn list_some_required() {
+        let rules = RecallSettings::default();
+        let list = ListAnswer::new_some("red, blue, yellow", 2);
+        assert!(rules.test_list(&list, "red, yellow").0.is_complete());
+        assert!(!rules.test_list(&list, "red").0.is_complete());
+        assert!(!rules.test_list(&list, "red, yellow, green").0.is_complete());
+    }
+
+    #[test]
+    fn list_separator() {
+        let rules = RecallSettings {
+            list_separator: ';',
+            ..Default::default()
+        };
+        let list = ListAnswer::new("1, 2; 3, 4");
+        assert!(rules.test_list(&list, "3, 4; 1, 2").0.is_complete());
+        assert!(!rules.test_list(&list, "1; 2; 3; 4").0.is_complete());
+    }
+}
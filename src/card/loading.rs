@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     fmt::{self, Display},
     fs::File,
     io::{self, Read},
@@ -17,12 +18,16 @@ use nom::{
     Finish, Parser,
 };
 use smartstring::alias::String;
+use unicode_normalization::UnicodeNormalization;
 
-use super::{Flashcard, McCard, RecallSettings, RecallType, Set, Side};
+use super::{
+    AnswerPattern, DecoyMode, Flashcard, Language, ListAnswer, McCard, Normalization,
+    OptionalAnswer, PrefixMode, Punctuation, RecallSettings, RecallType, Set, Side, Typos,
+};
 
-type IResult<I, O> = nom::IResult<I, O, Error>;
+pub(crate) type IResult<I, O> = nom::IResult<I, O, Error>;
 
-type Span<'a> = nom_locate::LocatedSpan<&'a str>;
+pub(crate) type Span<'a> = nom_locate::LocatedSpan<&'a str>;
 
 impl Set {
     /// Loads a set from a file.
@@ -58,6 +63,17 @@ fn second_line(s: Span<'_>) -> IResult<Span<'_>, Option<u32>> {
 
 fn body(mut s: Span<'_>) -> IResult<Span<'_>, Set> {
     let mut set = Set::default();
+    // Cards with the same id would share their progress.
+    let mut ids = HashSet::new();
+    let mut check_id = |id, line: Span<'_>| {
+        if ids.insert(id) {
+            Ok(())
+        } else {
+            Err(nom::Err::Failure(Error::DuplicateId {
+                line: line.location_line(),
+            }))
+        }
+    };
     while let Ok((rem, line)) = terminated(not_line_ending::<_, Error>, opt(newline))(s) {
         s = rem;
         s = match line.trim() {
@@ -65,12 +81,14 @@ fn body(mut s: Span<'_>) -> IResult<Span<'_>, Set> {
             "@[card back]" => set.recall_back.update(s)?.0,
             "@[mc]" => set.recall_mc.update(s)?.0,
             "[card]" => {
-                let (s, card) = Flashcard::parse(s)?;
+                let (s, card) = Flashcard::parse(s, &set)?;
+                check_id(card.id(), line)?;
                 set.flashcards.push(card);
                 s
             }
             "[mc]" => {
-                let (s, card) = McCard::parse(s)?;
+                let (s, card) = McCard::parse(s, &set)?;
+                check_id(card.id(), line)?;
                 set.mc_cards.push(card);
                 s
             }
@@ -84,7 +102,7 @@ fn property_separator(s: Span<'_>) -> IResult<Span<'_>, ()> {
     value((), pair(char(':'), space0))(s)
 }
 
-fn property_value(s: Span<'_>) -> IResult<Span<'_>, (Span<'_>, Span<'_>)> {
+pub(crate) fn property_value(s: Span<'_>) -> IResult<Span<'_>, (Span<'_>, Span<'_>)> {
     pair(
         terminated(
             take_till1(|ch| matches!(ch, ':' | '\n')),
@@ -116,6 +134,90 @@ impl RecallSettings {
                         })
                     })?
                 }
+                "check accents" => {
+                    self.check_accents = value.parse().map_err(|_| {
+                        nom::Err::Failure(Error::InvalidType {
+                            line: property.location_line(),
+                            expected: "{ true | false }",
+                        })
+                    })?
+                }
+                "collapse whitespace" => {
+                    self.collapse_whitespace = value.parse().map_err(|_| {
+                        nom::Err::Failure(Error::InvalidType {
+                            line: property.location_line(),
+                            expected: "{ true | false }",
+                        })
+                    })?
+                }
+                "ignore punctuation" => {
+                    self.ignore_punctuation = Punctuation::from_str(value).ok_or(
+                        nom::Err::Failure(Error::InvalidType {
+                            line: property.location_line(),
+                            expected: Punctuation::EXPECTED_VALUES,
+                        }),
+                    )?
+                }
+                "allowed typos" => {
+                    self.allowed_typos =
+                        Typos::from_str(value).ok_or(nom::Err::Failure(Error::InvalidType {
+                            line: property.location_line(),
+                            expected: Typos::EXPECTED_VALUES,
+                        }))?
+                }
+                "language" => self.language = Some(Language::new(value)),
+                "normalization" => {
+                    self.normalization = Normalization::from_str(value).ok_or(nom::Err::Failure(
+                        Error::InvalidType {
+                            line: property.location_line(),
+                            expected: Normalization::EXPECTED_VALUES,
+                        },
+                    ))?
+                }
+                "list separator" => {
+                    let mut chars = value.chars();
+                    self.list_separator =
+                        chars
+                            .next()
+                            .filter(|_| chars.next().is_none())
+                            .ok_or(nom::Err::Failure(Error::InvalidType {
+                                line: property.location_line(),
+                                expected: "single character",
+                            }))?
+                }
+                "ignore prefixes" => {
+                    self.ignore_prefixes = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|prefix| !prefix.is_empty())
+                        .map(card_text)
+                        .collect()
+                }
+                "borrow decoys" => {
+                    self.borrow_decoys = value.parse().map_err(|_| {
+                        nom::Err::Failure(Error::InvalidType {
+                            line: property.location_line(),
+                            expected: "{ true | false }",
+                        })
+                    })?
+                }
+                "none of the above" => self.none_of_the_above = chance(property, value)?,
+                "all of the above" => self.all_of_the_above = chance(property, value)?,
+                "decoys" => {
+                    self.decoys =
+                        DecoyMode::from_str(value).ok_or(nom::Err::Failure(Error::InvalidType {
+                            line: property.location_line(),
+                            expected: DecoyMode::EXPECTED_VALUES,
+                        }))?
+                }
+                "prefix mode" => {
+                    self.prefix_mode = PrefixMode::from_str(value).ok_or(nom::Err::Failure(
+                        Error::InvalidType {
+                            line: property.location_line(),
+                            expected: PrefixMode::EXPECTED_VALUES,
+                        },
+                    ))?
+                }
                 _ => {}
             }
         }
@@ -136,32 +238,145 @@ impl RecallType {
     }
 }
 
+impl Punctuation {
+    const EXPECTED_VALUES: &str = "{ none | all | <punctuation characters> }";
+
+    /// Reads a punctuation setting, rejecting lists containing letters or
+    /// digits since those are most likely misspelled keywords.
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(Self::None),
+            "all" => Some(Self::All),
+            chars if chars.chars().any(char::is_alphanumeric) => None,
+            chars => Some(Self::Only(
+                chars.chars().filter(|ch| !ch.is_whitespace()).collect(),
+            )),
+        }
+    }
+}
+
+impl Typos {
+    const EXPECTED_VALUES: &str = "{ <count> | <percent>% }";
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s.strip_suffix('%') {
+            Some(percent) => percent.trim_end().parse().ok().map(Self::Percent),
+            None => s.parse().ok().map(Self::Count),
+        }
+    }
+}
+
+impl Normalization {
+    const EXPECTED_VALUES: &str = "{ nfc | nfkc }";
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "nfc" => Some(Self::Nfc),
+            "nfkc" => Some(Self::Nfkc),
+            _ => None,
+        }
+    }
+}
+
+impl PrefixMode {
+    const EXPECTED_VALUES: &str = "{ optional | strict }";
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "optional" => Some(Self::Optional),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+impl DecoyMode {
+    const EXPECTED_VALUES: &str = "{ random | similar }";
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "random" => Some(Self::Random),
+            "similar" => Some(Self::Similar),
+            _ => None,
+        }
+    }
+}
+
 impl Flashcard {
-    fn parse(mut s: Span<'_>) -> IResult<Span<'_>, Self> {
+    /// Reads a card, using the settings in `set` so far to check list answers.
+    fn parse<'a>(mut s: Span<'a>, set: &Set) -> IResult<Span<'a>, Self> {
         let mut card = Self::blank();
         while let Ok((rem, (property, value))) = property_value(s) {
             s = rem;
-            let side = match property.trim() {
-                "F" => Side::Front,
-                "B" => Side::Back,
+            let (property_name, list) = split_list(property.trim());
+            let side = match property_name {
+                "F" | "F/" | "F optional" => Side::Front,
+                "B" | "B/" | "B optional" => Side::Back,
+                "ID" => {
+                    card.id_text = Some(card_text(value.trim()));
+                    continue;
+                }
+                "T" => {
+                    card.tags.extend(
+                        value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|tag| !tag.is_empty())
+                            .map(card_text),
+                    );
+                    continue;
+                }
                 _ => continue,
             };
-            card[side].push_text(string_from_escaped(value.trim_start()));
+            let rules = set.flashcard_recall_settings(side);
+            if let Some(required) = list {
+                if property_name.ends_with('/') {
+                    return Err(nom::Err::Failure(Error::ParseError {
+                        line: property.location_line(),
+                    }));
+                }
+                card[side].push_list(list_answer(property, required, value, rules)?);
+            } else if property_name.ends_with('/') {
+                card[side].push_pattern(answer_pattern(property, value)?);
+            } else if property_name.ends_with(" optional") {
+                card[side].push_optional(optional_answer(value, rules.normalization));
+            } else {
+                card[side].push_text(answer_text(value.trim_start(), rules.normalization));
+            }
         }
         Ok((s, card))
     }
 }
 
 impl McCard {
-    fn parse(mut s: Span<'_>) -> IResult<Span<'_>, Self> {
+    /// Reads a card, using the settings in `set` so far to check list answers
+    /// and normalize answers.
+    fn parse<'a>(mut s: Span<'a>, set: &Set) -> IResult<Span<'a>, Self> {
         let mut card = Self::blank();
+        let rules = &set.recall_mc;
         while let Ok((rem, (property, value))) = property_value(s) {
             s = rem;
-            let value = string_from_escaped(value.trim_start());
-            match property.trim() {
-                "Q" => card.question.push_text(value),
-                "A" => card.answer.push_text(value),
-                "D" => card.decoys.push_text(value),
+            match split_list(property.trim()) {
+                ("Q", None) => card.question.push_text(card_text(value.trim_start())),
+                ("A", None) => card
+                    .answer
+                    .push_text(answer_text(value.trim_start(), rules.normalization)),
+                ("A/", None) => card.answer.push_pattern(answer_pattern(property, value)?),
+                ("A optional", None) => card
+                    .answer
+                    .push_optional(optional_answer(value, rules.normalization)),
+                ("A", Some(required)) => card
+                    .answer
+                    .push_list(list_answer(property, required, value, rules)?),
+                ("A/", Some(_)) => {
+                    return Err(nom::Err::Failure(Error::ParseError {
+                        line: property.location_line(),
+                    }))
+                }
+                ("D", None) => card
+                    .decoys
+                    .push_text(answer_text(value.trim_start(), rules.normalization)),
+                ("ID", None) => card.id_text = Some(card_text(value.trim())),
                 _ => {}
             }
         }
@@ -169,12 +384,110 @@ impl McCard {
     }
 }
 
-fn string_from_escaped(s: &str) -> String {
+/// Reads a percent chance such as "25%".
+fn chance(property: Span<'_>, value: &str) -> std::result::Result<u32, nom::Err<Error>> {
+    value
+        .strip_suffix('%')
+        .and_then(|percent| percent.trim_end().parse().ok())
+        .filter(|&percent| percent <= 100)
+        .ok_or(nom::Err::Failure(Error::InvalidType {
+            line: property.location_line(),
+            expected: "<percent>%",
+        }))
+}
+
+/// Compiles the pattern on a line such as "B/: colou?r".  Patterns are not
+/// unescaped.
+fn answer_pattern(
+    property: Span<'_>,
+    value: Span<'_>,
+) -> std::result::Result<AnswerPattern, nom::Err<Error>> {
+    let source = value.trim().nfc().collect::<std::string::String>();
+    AnswerPattern::new(&source).map_err(|_| {
+        nom::Err::Failure(Error::InvalidType {
+            line: property.location_line(),
+            expected: "regular expression",
+        })
+    })
+}
+
+/// Splits a property name such as "B list 2" into the name of the side and,
+/// for list answers, the count following "list", which is empty if there is
+/// none.
+fn split_list(property_name: &str) -> (&str, Option<&str>) {
+    let mut words = property_name.split_whitespace();
+    match (words.next(), words.next(), words.next(), words.next()) {
+        (Some(name), Some("list"), count, None) => (name, Some(count.unwrap_or(""))),
+        _ => (property_name, None),
+    }
+}
+
+/// Reads the list on a line such as "B list 2: red, blue, yellow", where
+/// `required` is the count following "list" in the property name.  The count
+/// has to be at least 1 and at most the number of items, split by the
+/// separator in `rules`.
+fn list_answer(
+    property: Span<'_>,
+    required: &str,
+    value: Span<'_>,
+    rules: &RecallSettings,
+) -> std::result::Result<ListAnswer, nom::Err<Error>> {
+    let separator = rules.list_separator;
+    let text = answer_text(value.trim_start(), rules.normalization);
+    if required.is_empty() {
+        return Ok(ListAnswer::new(text));
+    }
+    required
+        .parse()
+        .ok()
+        .map(|required| ListAnswer::new_some(text, required))
+        .filter(|list| (1..=list.item_count(separator)).contains(&list.required().unwrap_or(0)))
+        .ok_or(nom::Err::Failure(Error::InvalidType {
+            line: property.location_line(),
+            expected: "list [<count from 1 to the number of items>]",
+        }))
+}
+
+/// Unescapes and normalizes text written on a card.
+fn card_text(s: &str) -> String {
+    string_from_escaped(s, false).nfc().collect()
+}
+
+/// Unescapes an answer written on a card and normalizes it the way answers
+/// to it are, so decoys that only differ in form compare equal.
+fn answer_text(s: &str, normalization: Normalization) -> String {
+    normalize(&string_from_escaped(s, false), normalization)
+}
+
+/// Reads the answer on a line such as "B optional: (to) run".  Escaped
+/// parentheses and backslashes are left for [`OptionalAnswer::new`].
+fn optional_answer(value: Span<'_>, normalization: Normalization) -> OptionalAnswer {
+    let source = normalize(
+        &string_from_escaped(value.trim_start(), true),
+        normalization,
+    );
+    OptionalAnswer::new(&source)
+}
+
+fn normalize(s: &str, normalization: Normalization) -> String {
+    match normalization {
+        Normalization::Nfc => s.nfc().collect(),
+        Normalization::Nfkc => s.nfkc().collect(),
+    }
+}
+
+/// Replaces escapes in `s` with the characters they stand for.  If
+/// `keep_parens` is true, "\\", "\(", and "\)" are left as written.
+fn string_from_escaped(s: &str, keep_parens: bool) -> String {
     let mut buf = String::new();
     let mut chars = s.chars();
     while let Some(char) = chars.next() {
         if char == '\\' {
             match chars.next() {
+                Some(ch @ ('\\' | '(' | ')')) if keep_parens => {
+                    buf.push('\\');
+                    buf.push(ch);
+                }
                 Some('\\') => buf.push('\\'),
                 Some('n') => buf.push('\n'),
                 Some(' ') => buf.push(' '),
@@ -196,6 +509,9 @@ pub enum Error {
     ParseError { line: u32 },
     /// Attempt to assign incorrect type to property.
     InvalidType { line: u32, expected: &'static str },
+    /// Card with the same id as an earlier card, so the two would share
+    /// progress.
+    DuplicateId { line: u32 },
 }
 
 impl Display for Error {
@@ -209,6 +525,11 @@ impl Display for Error {
                     "Property on line {line} expects value of type {expected}"
                 )
             }
+            Error::DuplicateId { line } => write!(
+                f,
+                "Card on line {line} starts the same as an earlier card; \
+                 add an \"ID:\" line to one of them to tell them apart"
+            ),
         }
     }
 }
@@ -255,7 +576,7 @@ impl Version {
         }
     }
 
-    fn parse(s: Span<'_>) -> IResult<Span<'_>, Self> {
+    pub(crate) fn parse(s: Span<'_>) -> IResult<Span<'_>, Self> {
         separated_pair(
             separated_pair(cc::u32, char('.'), cc::u32),
             char('.'),
@@ -328,35 +649,343 @@ mod tests {
         assert!(rem.is_empty());
     }
 
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn recall_settings_check_accents() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("check accents: false\n".into()).unwrap();
+        assert_eq!(rules.check_accents, false);
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules.update(" check accents : true \n".into()).unwrap();
+        assert_eq!(rules.check_accents, true);
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn recall_settings_collapse_whitespace() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("collapse whitespace: true\n".into()).unwrap();
+        assert_eq!(rules.collapse_whitespace, true);
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules
+            .update(" collapse whitespace : false \n".into())
+            .unwrap();
+        assert_eq!(rules.collapse_whitespace, false);
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    fn recall_settings_ignore_punctuation() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("ignore punctuation: all\n".into()).unwrap();
+        assert_eq!(rules.ignore_punctuation, Punctuation::All);
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules.update("ignore punctuation: . - '\n".into()).unwrap();
+        assert_eq!(rules.ignore_punctuation, Punctuation::Only(".-'".into()));
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules
+            .update(" ignore punctuation : none \n".into())
+            .unwrap();
+        assert_eq!(rules.ignore_punctuation, Punctuation::None);
+        assert!(rem.is_empty());
+
+        assert!(rules.update("ignore punctuation: true\n".into()).is_err());
+        assert!(rules.update("ignore punctuation: . a\n".into()).is_err());
+        assert_eq!(rules.ignore_punctuation, Punctuation::None);
+    }
+
+    #[test]
+    fn recall_settings_allowed_typos() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("allowed typos: 2\n".into()).unwrap();
+        assert_eq!(rules.allowed_typos, Typos::Count(2));
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules.update(" allowed typos : 15% \n".into()).unwrap();
+        assert_eq!(rules.allowed_typos, Typos::Percent(15));
+        assert!(rem.is_empty());
+
+        assert!(rules.update("allowed typos: some\n".into()).is_err());
+    }
+
+    #[test]
+    fn recall_settings_language() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("language: tr\n".into()).unwrap();
+        assert_eq!(rules.language, Some(Language::new("tr")));
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules.update(" language : de-AT \n".into()).unwrap();
+        assert_eq!(rules.language, Some(Language::new("de-AT")));
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    fn recall_settings_normalization() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("normalization: nfkc\n".into()).unwrap();
+        assert_eq!(rules.normalization, Normalization::Nfkc);
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules.update(" normalization : nfc \n".into()).unwrap();
+        assert_eq!(rules.normalization, Normalization::Nfc);
+        assert!(rem.is_empty());
+
+        assert!(rules.update("normalization: nfd\n".into()).is_err());
+    }
+
+    #[test]
+    fn recall_settings_list_separator() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules.update("list separator: ;\n".into()).unwrap();
+        assert_eq!(rules.list_separator, ';');
+        assert!(rem.is_empty());
+
+        assert!(rules.update("list separator: ;;\n".into()).is_err());
+    }
+
+    #[test]
+    fn recall_settings_prefixes() {
+        let mut rules = RecallSettings::default();
+
+        let (rem, ()) = rules
+            .update("ignore prefixes: der, die,das \nprefix mode: strict\n".into())
+            .unwrap();
+        assert_eq!(rules.ignore_prefixes, ["der", "die", "das"]);
+        assert_eq!(rules.prefix_mode, PrefixMode::Strict);
+        assert!(rem.is_empty());
+
+        let (rem, ()) = rules.update("prefix mode: optional\n".into()).unwrap();
+        assert_eq!(rules.prefix_mode, PrefixMode::Optional);
+        assert!(rem.is_empty());
+
+        assert!(rules.update("prefix mode: required\n".into()).is_err());
+    }
+
+    #[test]
+    fn recall_settings_decoys() {
+        let mut rules = RecallSettings::default();
+        let (rem, ()) = rules.update("decoys: similar\n".into()).unwrap();
+        assert_eq!(rules.decoys, DecoyMode::Similar);
+        assert!(rem.is_empty());
+
+        assert!(rules.update("decoys: clever\n".into()).is_err());
+
+        let (_, ()) = rules.update("borrow decoys: true\n".into()).unwrap();
+        assert!(rules.borrow_decoys);
+        assert!(rules.update("borrow decoys: yes\n".into()).is_err());
+
+        let (_, ()) = rules
+            .update("none of the above: 25%\nall of the above: 100 %\n".into())
+            .unwrap();
+        assert_eq!(rules.none_of_the_above, 25);
+        assert_eq!(rules.all_of_the_above, 100);
+        assert!(rules.update("none of the above: 25\n".into()).is_err());
+        assert!(rules.update("all of the above: 101%\n".into()).is_err());
+    }
+
+    #[test]
+    fn card_id_text() {
+        let (_, card) =
+            Flashcard::parse("ID: run\nF: correr\nB: to run\n".into(), &Set::default()).unwrap();
+        assert_eq!(card.id_text.as_deref(), Some("run"));
+        let (_, edited) =
+            Flashcard::parse("F: corer\nB: run\nID:  run \n".into(), &Set::default()).unwrap();
+        assert_eq!(edited.id(), card.id());
+
+        let (_, card) = McCard::parse("Q: q\nA: a\nID: q1\n".into(), &Set::default()).unwrap();
+        assert_eq!(card.id_text.as_deref(), Some("q1"));
+    }
+
+    #[test]
+    fn duplicate_card_ids() {
+        let load = |s: &str| Set::load_from_reader(s.as_bytes());
+        let err = load("[card]\nF: bank\nB: shore\n\n[card]\nF: bank\nB: money\n").unwrap_err();
+        assert!(matches!(err, Error::DuplicateId { line: 5 }), "{err:?}");
+        assert!(err.to_string().contains("\"ID:\""));
+        assert!(
+            load("[card]\nF: bank\nB: shore\n\n[card]\nID: bank2\nF: bank\nB: money\n").is_ok()
+        );
+        // Flashcards and multiple choice cards can't share ids.
+        assert!(load("[card]\nF: bank\nB: shore\n\n[mc]\nQ: bank\nA: a\nD: b\n").is_ok());
+    }
+
+    #[test]
+    fn flashcard_tags() {
+        let (rem, card) = Flashcard::parse(
+            "F: France\nB: Paris\nT: capitals, europe\nT: geo\n".into(),
+            &Set::default(),
+        )
+        .unwrap();
+        assert_eq!(card.tags, ["capitals", "europe", "geo"]);
+        assert_eq!(card.id(), Flashcard::new("France", "Paris").id());
+        assert!(rem.is_empty());
+    }
+
     #[test]
     fn flashcard_single_texts() {
-        let (rem, card) = Flashcard::parse("F: a\n B : 0\n".into()).unwrap();
+        let (rem, card) = Flashcard::parse("F: a\n B : 0\n".into(), &Set::default()).unwrap();
         assert_eq!(card, Flashcard::new("a", "0"));
         assert!(rem.is_empty());
     }
 
     #[test]
     fn flashcard_multiple_texts() {
-        let (rem, card) = Flashcard::parse("F: a\nF: A\nB: 0\nB: )\n".into()).unwrap();
+        let (rem, card) =
+            Flashcard::parse("F: a\nF: A\nB: 0\nB: )\n".into(), &Set::default()).unwrap();
         assert_eq!(
             card,
             Flashcard {
                 front: CardSide::new_multi(["a", "A"]),
                 back: CardSide::new_multi(["0", ")"]),
+                tags: Vec::new(),
+                id_text: None,
+            }
+        );
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    fn flashcard_normalized_texts() {
+        let (rem, card) =
+            Flashcard::parse("F: cafe\u{301}\nB: caf\u{e9}\n".into(), &Set::default()).unwrap();
+        assert_eq!(card, Flashcard::new("caf\u{e9}", "caf\u{e9}"));
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    fn flashcard_nfkc_texts() {
+        let (set, _) = Set::load_from_reader(
+            "@[card back]\nnormalization: nfkc\n\n[card]\nF: \u{fb01}le\nB: \u{fb01}le\n\n\
+             [mc]\nQ: q\nA: a\nD: \u{fb01}\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let card = &set.flashcards[0];
+        // Only the back is compared using NFKC.
+        assert_eq!(card.front, CardSide::new("\u{fb01}le"));
+        assert_eq!(card.back, CardSide::new("file"));
+        assert!(card.back.matches_text(&set.recall_back, "file"));
+        assert!(card.back.matches_text(&set.recall_back, "\u{fb01}le"));
+        assert_eq!(set.mc_cards[0].decoys.iter_text().next(), Some("\u{fb01}"));
+    }
+
+    #[test]
+    fn flashcard_patterns() {
+        let (rem, card) =
+            Flashcard::parse("F: color\nB/: colou?r\nB/: \\d+\n".into(), &Set::default()).unwrap();
+        let mut back = CardSide::empty();
+        back.push_pattern(AnswerPattern::new("colou?r").unwrap());
+        back.push_pattern(AnswerPattern::new("\\d+").unwrap());
+        assert_eq!(
+            card,
+            Flashcard {
+                front: CardSide::new("color"),
+                back,
+                tags: Vec::new(),
+                id_text: None,
             }
         );
         assert!(rem.is_empty());
+
+        assert!(Flashcard::parse("B/: colou?r(\n".into(), &Set::default()).is_err());
+    }
+
+    #[test]
+    fn flashcard_lists() {
+        let (rem, card) = Flashcard::parse(
+            "F: primary colors\nB list: red, blue, yellow\nB list 2: a, b, c\n".into(),
+            &Set::default(),
+        )
+        .unwrap();
+        let mut back = CardSide::empty();
+        back.push_list(ListAnswer::new("red, blue, yellow"));
+        back.push_list(ListAnswer::new_some("a, b, c", 2));
+        assert_eq!(
+            card,
+            Flashcard {
+                front: CardSide::new("primary colors"),
+                back,
+                tags: Vec::new(),
+                id_text: None,
+            }
+        );
+        assert!(rem.is_empty());
+
+        assert!(Flashcard::parse("B list two: a, b, c\n".into(), &Set::default()).is_err());
+        assert!(Flashcard::parse("B list 0: a, b, c\n".into(), &Set::default()).is_err());
+        assert!(Flashcard::parse("B list 4: a, b, c\n".into(), &Set::default()).is_err());
+        assert!(Flashcard::parse("F/ list: a, b\n".into(), &Set::default()).is_err());
+        assert!(McCard::parse("A/ list: a, b\n".into(), &Set::default()).is_err());
+        let (_, card) = Flashcard::parse("B listing: a, b\n".into(), &Set::default()).unwrap();
+        assert_eq!(card.back.lists().len(), 0);
+
+        let mut set = Set::default();
+        set.recall_back.list_separator = ';';
+        assert!(Flashcard::parse("B list 2: a, b; c\n".into(), &set).is_ok());
+        assert!(Flashcard::parse("B list 3: a, b; c\n".into(), &set).is_err());
+
+        let (_, card) = McCard::parse(
+            "Q: primary colors\nA list: red, blue, yellow\n".into(),
+            &Set::default(),
+        )
+        .unwrap();
+        assert_eq!(card.answer.lists().len(), 1);
+    }
+
+    #[test]
+    fn flashcard_optional() {
+        let (rem, card) = Flashcard::parse(
+            "F: correr\nB optional: (to) run\nB optional: f\\(x\\) \\\\n\nB: f(x)\n".into(),
+            &Set::default(),
+        )
+        .unwrap();
+        let mut back = CardSide::new("f(x)");
+        back.push_optional(OptionalAnswer::new("(to) run"));
+        back.push_optional(OptionalAnswer::new("f\\(x\\) \\\\n"));
+        assert_eq!(
+            card,
+            Flashcard {
+                front: CardSide::new("correr"),
+                back,
+                tags: Vec::new(),
+                id_text: None,
+            }
+        );
+        assert_eq!(
+            card.back.iter_text().collect::<Vec<_>>(),
+            ["f(x)", "(to) run", "f(x) \\n"]
+        );
+        assert!(rem.is_empty());
+
+        let (_, card) =
+            McCard::parse("Q: correr\nA optional: (to) run\n".into(), &Set::default()).unwrap();
+        assert_eq!(card.answer.optional().len(), 1);
     }
 
     #[test]
     fn mc_card_single_texts() {
-        let (rem, card) = McCard::parse("Q: 0mc\n A : 0answer\nD: 0decoy0\n".into()).unwrap();
+        let (rem, card) =
+            McCard::parse("Q: 0mc\n A : 0answer\nD: 0decoy0\n".into(), &Set::default()).unwrap();
         assert_eq!(
             card,
             McCard {
                 question: "0mc".into(),
                 answer: "0answer".into(),
                 decoys: ["0decoy0"].into_iter().collect(),
+                id_text: None,
             }
         );
         assert!(rem.is_empty());
@@ -366,6 +995,7 @@ mod tests {
     fn mc_card_multiple_texts() {
         let (rem, card) = McCard::parse(
             "Q: 0mc\nQ: 0MC\nA: 0answer\nA: 0ANSWER\nD: 0decoy0\nD: 0decoy1\nD: 0decoy2\n".into(),
+            &Set::default(),
         )
         .unwrap();
         assert_eq!(
@@ -374,6 +1004,7 @@ mod tests {
                 question: CardSide::new_multi(["0mc", "0MC"]),
                 answer: CardSide::new_multi(["0answer", "0ANSWER"]),
                 decoys: ["0decoy0", "0decoy1", "0decoy2"].into_iter().collect(),
+                id_text: None,
             }
         );
         assert!(rem.is_empty());
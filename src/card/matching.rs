@@ -0,0 +1,835 @@
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+
+use super::{
+    AnswerPattern, Language, Leniency, Normalization, OptionalAnswer, PrefixMode, Punctuation,
+    RecallSettings, Typos,
+};
+
+impl RecallSettings {
+    /// Returns how lenient these rules had to be for `text` to match
+    /// `template`, or `None` if it doesn't match.
+    ///
+    /// Leading words in [`RecallSettings::ignore_prefixes`] are handled
+    /// according to [`RecallSettings::prefix_mode`].
+    pub(crate) fn test_match(&self, template: &str, text: &str) -> Option<Leniency> {
+        let (template_prefix, template_rest) = self.split_prefix(template);
+        let (text_prefix, text_rest) = self.split_prefix(text);
+        if template_prefix.is_none() && text_prefix.is_none() {
+            return self.test_text(template, text);
+        }
+
+        match self.prefix_mode {
+            // Compare with the prefixes as well so typos in them still count
+            // as typos rather than as a different word.
+            PrefixMode::Optional => {
+                let stripped = self
+                    .test_text(template_rest, text_rest)
+                    .map(|leniency| Leniency {
+                        prefix: true,
+                        ..leniency
+                    });
+                [self.test_text(template, text), stripped]
+                    .into_iter()
+                    .flatten()
+                    .min_by_key(Leniency::preference)
+            }
+            PrefixMode::Strict => match (template_prefix, text_prefix) {
+                (Some(expected), Some(given)) => {
+                    let prefix = self.without_typos().test_text(expected, given)?;
+                    let rest = self.test_text(template_rest, text_rest)?;
+                    Some(prefix.combine(rest))
+                }
+                (Some(_), None) => None,
+                (None, _) => self.test_text(template, text),
+            },
+        }
+    }
+
+    /// [`RecallSettings::test_match`] for an answer with optional parts, which
+    /// matches if any way of writing it with or without them does.
+    pub(crate) fn test_optional(&self, answer: &OptionalAnswer, text: &str) -> Option<Leniency> {
+        answer
+            .expansions()
+            .filter_map(|template| self.test_match(template, text))
+            .min_by_key(Leniency::preference)
+    }
+
+    /// [`RecallSettings::test_match`] ignoring prefixes.
+    fn test_text(&self, template: &str, text: &str) -> Option<Leniency> {
+        let allowed = self.allowed_leniency();
+        let typos = self.distance_within(template, text, allowed, self.typo_limit(template))?;
+
+        // Find which of the allowed relaxations the match depends on.
+        let needs = |without: Leniency| {
+            self.distance_within(template, text, without, typos)
+                .is_none()
+        };
+        Some(Leniency {
+            caps: allowed.caps
+                && needs(Leniency {
+                    caps: false,
+                    ..allowed
+                }),
+            accents: allowed.accents
+                && needs(Leniency {
+                    accents: false,
+                    ..allowed
+                }),
+            whitespace: allowed.whitespace
+                && needs(Leniency {
+                    whitespace: false,
+                    ..allowed
+                }),
+            punctuation: allowed.punctuation
+                && needs(Leniency {
+                    punctuation: false,
+                    ..allowed
+                }),
+            prefix: false,
+            typos,
+        })
+    }
+
+    /// Returns how lenient these rules had to be for `text` to match
+    /// `pattern`, or `None` if it doesn't match.
+    ///
+    /// Only capitalization can be relaxed; patterns are expected to spell out
+    /// any other variations they accept.
+    pub(crate) fn test_pattern(&self, pattern: &AnswerPattern, text: &str) -> Option<Leniency> {
+        let text = self.normalize(text.trim());
+        if pattern.is_match(&text, false) {
+            Some(Leniency::default())
+        } else if !self.check_caps && pattern.is_match(&text, true) {
+            Some(Leniency {
+                caps: true,
+                ..Default::default()
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Splits a leading word in [`RecallSettings::ignore_prefixes`] off `s`,
+    /// returning it and the rest of `s`.  Capitalization of the word doesn't
+    /// matter.
+    fn split_prefix<'s>(&self, s: &'s str) -> (Option<&'s str>, &'s str) {
+        let s = s.trim_start();
+        if let Some((word, rest)) = s.split_once(char::is_whitespace) {
+            let caps = Leniency {
+                caps: true,
+                ..Default::default()
+            };
+            let word_key = self.fold(word, caps);
+            if self
+                .ignore_prefixes
+                .iter()
+                .any(|prefix| self.fold(prefix, caps) == word_key)
+            {
+                return (Some(word), rest);
+            }
+        }
+        (None, s)
+    }
+
+    /// These rules without allowing any typos.
+    pub(crate) fn without_typos(&self) -> Self {
+        Self {
+            allowed_typos: Typos::Count(0),
+            ..self.clone()
+        }
+    }
+
+    /// The most lenient these rules allow comparisons to be, not counting
+    /// typos.
+    fn allowed_leniency(&self) -> Leniency {
+        Leniency {
+            caps: !self.check_caps,
+            accents: !self.check_accents,
+            whitespace: self.collapse_whitespace,
+            punctuation: self.ignore_punctuation != Punctuation::None,
+            prefix: false,
+            typos: 0,
+        }
+    }
+
+    /// How many typos an answer to `template` may have.
+    fn typo_limit(&self, template: &str) -> usize {
+        match self.allowed_typos {
+            Typos::Count(count) => count,
+            Typos::Percent(percent) => template.trim().chars().count() * percent as usize / 100,
+        }
+    }
+
+    /// Returns the edit distance between `template` and `text` when compared
+    /// with `leniency`, or `None` if it is more than `limit`.
+    fn distance_within(
+        &self,
+        template: &str,
+        text: &str,
+        leniency: Leniency,
+        limit: usize,
+    ) -> Option<usize> {
+        // Try removing punctuation and replacing it with spaces so "Dr."
+        // matches "Dr" and "well-known" matches "well known".
+        let modes: &[_] = if leniency.punctuation {
+            &[PunctuationMode::Remove, PunctuationMode::Separate]
+        } else {
+            &[PunctuationMode::Keep]
+        };
+        modes
+            .iter()
+            .filter_map(|&mode| {
+                let template = self.key(template, leniency, mode);
+                let text = self.key(text, leniency, mode);
+                if template == text {
+                    Some(0)
+                } else if limit == 0 {
+                    None
+                } else {
+                    let template = template.chars().collect::<Vec<_>>();
+                    let text = text.chars().collect::<Vec<_>>();
+                    Some(edit_distance(&template, &text)).filter(|&distance| distance <= limit)
+                }
+            })
+            .min()
+    }
+
+    /// The form of `s` compared by [`RecallSettings::test_match`].
+    fn key(
+        &self,
+        s: &str,
+        leniency: Leniency,
+        punctuation: PunctuationMode,
+    ) -> std::string::String {
+        let mut s = self.fold(s.trim(), leniency);
+        match punctuation {
+            PunctuationMode::Keep => {}
+            PunctuationMode::Remove => s.retain(|ch| !self.ignore_punctuation.contains(ch)),
+            PunctuationMode::Separate => {
+                s = s
+                    .chars()
+                    .map(|ch| {
+                        if self.ignore_punctuation.contains(ch) {
+                            ' '
+                        } else {
+                            ch
+                        }
+                    })
+                    .collect();
+                s = s.trim().into();
+            }
+        }
+        if leniency.whitespace {
+            s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        s
+    }
+
+    /// The form of a single grapheme compared when lining up an answer with
+    /// the text it should have matched, or `None` if `punctuation` removes
+    /// it.  Whitespace, and punctuation `punctuation` replaces with spaces,
+    /// are " " if these rules collapse whitespace.
+    pub(crate) fn grapheme_key(
+        &self,
+        grapheme: &str,
+        punctuation: PunctuationMode,
+    ) -> Option<std::string::String> {
+        let ignored = grapheme
+            .chars()
+            .all(|ch| self.ignore_punctuation.contains(ch));
+        match punctuation {
+            PunctuationMode::Remove if ignored => None,
+            PunctuationMode::Separate if ignored => Some(" ".into()),
+            _ if self.collapse_whitespace && grapheme.chars().all(char::is_whitespace) => {
+                Some(" ".into())
+            }
+            _ => Some(self.fold(grapheme, self.allowed_leniency())),
+        }
+    }
+
+    /// The ways [`RecallSettings::test_match`] tries treating ignored
+    /// punctuation; an answer matches if it does with any of them.
+    pub(crate) fn punctuation_modes(&self) -> &'static [PunctuationMode] {
+        if self.ignore_punctuation != Punctuation::None {
+            &[PunctuationMode::Remove, PunctuationMode::Separate]
+        } else {
+            &[PunctuationMode::Keep]
+        }
+    }
+
+    /// Normalizes `s` and removes differences in case and accents allowed by
+    /// `leniency`.
+    fn fold(&self, s: &str, leniency: Leniency) -> std::string::String {
+        let mut s = self.normalize(s);
+        if leniency.caps {
+            // Case folding can leave text unnormalized.
+            s = self.normalize(&self.fold_case(&s));
+        }
+        if leniency.accents {
+            s = self.normalize(&strip_accents(&s));
+        }
+        s
+    }
+
+    fn normalize(&self, s: &str) -> std::string::String {
+        match self.normalization {
+            Normalization::Nfc => s.nfc().collect(),
+            Normalization::Nfkc => s.nfkc().collect(),
+        }
+    }
+
+    /// Folds the case of `s` using the rules for the language of this side.
+    fn fold_case(&self, s: &str) -> std::string::String {
+        match self.language.as_ref().map(Language::case_rules) {
+            Some(CaseRules::Turkic) => s
+                .chars()
+                .map(|ch| match ch {
+                    'I' => 'ı',
+                    'İ' => 'i',
+                    ch => ch,
+                })
+                .default_case_fold()
+                .collect(),
+            Some(CaseRules::Default) | None => s.chars().default_case_fold().collect(),
+        }
+    }
+}
+
+/// The number of insertions, deletions, substitutions, and swaps of adjacent
+/// elements needed to turn `a` into `b` (optimal string alignment
+/// Damerau-Levenshtein distance).
+pub(crate) fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    // Rows of the table for the last two elements of `a` and this one.
+    let mut before_prev = vec![0; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (prev[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_prev[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut current);
+    }
+    prev[b.len()]
+}
+
+/// One step in turning one sequence into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    /// The next elements of both are the same.
+    Keep,
+    /// The next element of the second sequence is missing from the first.
+    Insert,
+    /// The next element of the first sequence is missing from the second.
+    Delete,
+    /// The next elements of both are different.
+    Substitute,
+}
+
+/// Returns the fewest steps needed to turn `from` into `to` (Levenshtein
+/// distance), in order.
+pub(crate) fn align<T: PartialEq>(from: &[T], to: &[T]) -> Vec<Edit> {
+    let width = to.len() + 1;
+    let mut table = vec![0; (from.len() + 1) * width];
+    for i in 0..=from.len() {
+        for j in 0..=to.len() {
+            table[i * width + j] = if i == 0 {
+                j
+            } else if j == 0 {
+                i
+            } else {
+                let cost = usize::from(from[i - 1] != to[j - 1]);
+                (table[(i - 1) * width + j] + 1)
+                    .min(table[i * width + j - 1] + 1)
+                    .min(table[(i - 1) * width + j - 1] + cost)
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(from.len().max(to.len()));
+    let (mut i, mut j) = (from.len(), to.len());
+    while i > 0 || j > 0 {
+        let here = table[i * width + j];
+        if i > 0 && j > 0 && from[i - 1] == to[j - 1] && here == table[(i - 1) * width + j - 1] {
+            edits.push(Edit::Keep);
+            (i, j) = (i - 1, j - 1);
+        } else if i > 0 && j > 0 && here == table[(i - 1) * width + j - 1] + 1 {
+            edits.push(Edit::Substitute);
+            (i, j) = (i - 1, j - 1);
+        } else if i > 0 && here == table[(i - 1) * width + j] + 1 {
+            edits.push(Edit::Delete);
+            i -= 1;
+        } else {
+            edits.push(Edit::Insert);
+            j -= 1;
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+/// What [`RecallSettings::key`] does with punctuation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PunctuationMode {
+    Keep,
+    /// Remove ignored punctuation.
+    Remove,
+    /// Replace ignored punctuation with spaces.
+    Separate,
+}
+
+impl Punctuation {
+    /// Whether `ch` is punctuation that should be ignored.
+    fn contains(&self, ch: char) -> bool {
+        match self {
+            Punctuation::None => false,
+            Punctuation::All => is_punctuation(ch),
+            Punctuation::Only(chars) => chars.contains(ch),
+        }
+    }
+}
+
+/// Whether `ch` is ASCII punctuation or in one of the common Unicode punctuation
+/// blocks.
+fn is_punctuation(ch: char) -> bool {
+    ch.is_ascii_punctuation()
+        || matches!(
+            ch,
+            '¡' | '§' | '«' | '·' | '»' | '¿'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205e}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{ff01}'..='\u{ff0f}'
+                | '\u{ff1a}'..='\u{ff1f}'
+        )
+}
+
+/// Removes diacritics from `s`, so "niño" becomes "nino".
+fn strip_accents(s: &str) -> std::string::String {
+    s.nfd()
+        .filter(|&ch| !is_diacritic(ch))
+        .map(|ch| match ch {
+            // Letters that don't decompose into a base letter and a mark.
+            'ø' => 'o',
+            'Ø' => 'O',
+            'ł' => 'l',
+            'Ł' => 'L',
+            'đ' => 'd',
+            'Đ' => 'D',
+            'ħ' => 'h',
+            'Ħ' => 'H',
+            ch => ch,
+        })
+        .collect()
+}
+
+/// Whether `ch` is in one of the Combining Diacritical Marks blocks.
+fn is_diacritic(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{300}'..='\u{36f}'
+            | '\u{1ab0}'..='\u{1aff}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
+/// Language specific rules for comparing text without caring about case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseRules {
+    /// Unicode default case folding.
+    Default,
+    /// Turkish and Azerbaijani, where dotted and dotless i are different
+    /// letters.
+    Turkic,
+}
+
+impl Language {
+    fn case_rules(&self) -> CaseRules {
+        let primary = self.primary();
+        if ["tr", "az"]
+            .iter()
+            .any(|tag| primary.eq_ignore_ascii_case(tag))
+        {
+            CaseRules::Turkic
+        } else {
+            CaseRules::Default
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps_insensitive(language: Option<&str>) -> RecallSettings {
+        RecallSettings {
+            check_caps: false,
+            language: language.map(Language::new),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fold_default() {
+        let rules = caps_insensitive(None);
+        assert!(rules.test_match("Straße", "STRASSE").is_some());
+        assert!(rules.test_match("ΣΑΣ", "σας").is_some());
+        assert!(rules.test_match("I", "i").is_some());
+        assert!(rules.test_match("I", "ı").is_none());
+    }
+
+    #[test]
+    fn fold_turkish() {
+        let rules = caps_insensitive(Some("tr"));
+        assert!(rules.test_match("İstanbul", "istanbul").is_some());
+        assert!(rules.test_match("ISPARTA", "ısparta").is_some());
+        assert!(rules.test_match("ISPARTA", "isparta").is_none());
+        assert!(rules.test_match("İstanbul", "ıstanbul").is_none());
+
+        let rules = caps_insensitive(Some("az-Latn"));
+        assert!(rules.test_match("İ", "i").is_some());
+    }
+
+    #[test]
+    fn caps_ignored() {
+        let rules = caps_insensitive(None);
+        assert_eq!(
+            rules.test_match("Paris", "Paris"),
+            Some(Leniency::default())
+        );
+        assert_eq!(
+            rules.test_match("Paris", "paris"),
+            Some(Leniency {
+                caps: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn fold_german() {
+        let rules = caps_insensitive(Some("de-DE"));
+        assert!(rules.test_match("Straße", "STRASSE").is_some());
+        assert!(rules.test_match("STRAẞE", "straße").is_some());
+        assert!(rules.test_match("Fuß", "fuss").is_some());
+    }
+
+    #[test]
+    fn normalize_nfc() {
+        let rules = RecallSettings {
+            check_caps: true,
+            ..Default::default()
+        };
+        assert!(rules.test_match("caf\u{e9}", "cafe\u{301}").is_some());
+        assert!(rules.test_match("\u{fb01}sh", "fish").is_none());
+
+        let rules = caps_insensitive(None);
+        assert!(rules.test_match("CAFE\u{301}", "caf\u{e9}").is_some());
+    }
+
+    #[test]
+    fn normalize_nfkc() {
+        let rules = RecallSettings {
+            normalization: Normalization::Nfkc,
+            ..Default::default()
+        };
+        assert!(rules.test_match("caf\u{e9}", "cafe\u{301}").is_some());
+        assert!(rules.test_match("\u{fb01}sh", "fish").is_some());
+        assert!(rules.test_match("x\u{b2}", "x2").is_some());
+    }
+
+    #[test]
+    fn accents_checked() {
+        let rules = RecallSettings::default();
+        assert_eq!(rules.test_match("niño", "niño"), Some(Leniency::default()));
+        assert_eq!(rules.test_match("niño", "nino"), None);
+    }
+
+    #[test]
+    fn accents_ignored() {
+        let rules = RecallSettings {
+            check_accents: false,
+            ..Default::default()
+        };
+        let missing = Some(Leniency {
+            accents: true,
+            ..Default::default()
+        });
+        assert_eq!(rules.test_match("niño", "niño"), Some(Leniency::default()));
+        assert_eq!(rules.test_match("niño", "nino"), missing);
+        assert_eq!(rules.test_match("Crème Brûlée", "Creme Brulee"), missing);
+        assert_eq!(rules.test_match("Łódź", "Lodz"), missing);
+        assert_eq!(rules.test_match("nino", "niño"), missing);
+        assert_eq!(rules.test_match("niño", "nina"), None);
+    }
+
+    #[test]
+    fn whitespace_checked() {
+        let rules = RecallSettings::default();
+        assert!(rules.test_match(" well known ", "well known").is_some());
+        assert!(rules.test_match("well known", "well  known").is_none());
+    }
+
+    #[test]
+    fn whitespace_collapsed() {
+        let rules = RecallSettings {
+            collapse_whitespace: true,
+            ..Default::default()
+        };
+        let collapsed = Some(Leniency {
+            whitespace: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            rules.test_match("well known", "well known"),
+            Some(Leniency::default())
+        );
+        assert_eq!(rules.test_match("well known", "well \t known"), collapsed);
+        assert_eq!(rules.test_match("well known", "wellknown"), None);
+    }
+
+    #[test]
+    fn punctuation_all_ignored() {
+        let rules = RecallSettings {
+            ignore_punctuation: Punctuation::All,
+            ..Default::default()
+        };
+        let ignored = Some(Leniency {
+            punctuation: true,
+            ..Default::default()
+        });
+        assert_eq!(rules.test_match("Dr.", "Dr."), Some(Leniency::default()));
+        assert_eq!(rules.test_match("Dr.", "Dr"), ignored);
+        assert_eq!(rules.test_match("well-known", "well known"), ignored);
+        assert_eq!(rules.test_match("well-known", "wellknown"), ignored);
+        assert_eq!(rules.test_match("don't", "dont"), ignored);
+        assert_eq!(rules.test_match("¿Qué?", "Qué"), ignored);
+        assert_eq!(rules.test_match("Dr. Who", "Dr Who"), ignored);
+        assert_eq!(rules.test_match("Dr. Who", "Dr Whom"), None);
+    }
+
+    #[test]
+    fn punctuation_some_ignored() {
+        let rules = RecallSettings {
+            ignore_punctuation: Punctuation::Only("-".into()),
+            ..Default::default()
+        };
+        assert!(rules.test_match("well-known", "well known").is_some());
+        assert!(rules.test_match("Dr.", "Dr").is_none());
+    }
+
+    #[test]
+    fn punctuation_and_whitespace_ignored() {
+        let rules = RecallSettings {
+            collapse_whitespace: true,
+            ignore_punctuation: Punctuation::All,
+            ..Default::default()
+        };
+        assert_eq!(
+            rules.test_match("well - known", "well known"),
+            Some(Leniency {
+                whitespace: true,
+                punctuation: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn optional_parts() {
+        let rules = RecallSettings::default();
+        let to_run = OptionalAnswer::new("(to) run");
+        assert_eq!(
+            rules.test_optional(&to_run, "run"),
+            Some(Leniency::default())
+        );
+        assert!(rules.test_optional(&to_run, "to run").is_some());
+        assert!(rules.test_optional(&to_run, "(to) run").is_none());
+        assert!(rules.test_optional(&to_run, "to").is_none());
+        let house = OptionalAnswer::new("the (big) house");
+        assert!(rules.test_optional(&house, "the house").is_some());
+        assert!(rules.test_optional(&house, "the big house").is_some());
+        assert!(rules.test_optional(&house, "big house").is_none());
+        let nested = OptionalAnswer::new("a ((b) c)");
+        assert!(rules.test_optional(&nested, "a c").is_some());
+        assert!(rules.test_optional(&nested, "a b c").is_some());
+        assert!(rules.test_optional(&nested, "a (b) c").is_none());
+        let f = OptionalAnswer::new(r"f\(x\)");
+        assert!(rules.test_optional(&f, "f(x)").is_some());
+        assert!(rules.test_optional(&f, "f").is_none());
+
+        // Plain text has no optional parts.
+        assert!(rules.test_match("f(x)", "f").is_none());
+        assert!(rules.test_match("Paris (France)", "Paris").is_none());
+    }
+
+    #[test]
+    fn edit_distance_test() {
+        fn distance(a: &str, b: &str) -> usize {
+            let a = a.chars().collect::<Vec<_>>();
+            let b = b.chars().collect::<Vec<_>>();
+            edit_distance(&a, &b)
+        }
+
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("abc", ""), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("receive", "recieve"), 1);
+        assert_eq!(distance("ca", "abc"), 3);
+        assert_eq!(distance("niño", "nino"), 1);
+    }
+
+    #[test]
+    fn align_test() {
+        fn steps(from: &str, to: &str) -> Vec<Edit> {
+            let from = from.chars().collect::<Vec<_>>();
+            let to = to.chars().collect::<Vec<_>>();
+            align(&from, &to)
+        }
+
+        assert_eq!(steps("", ""), []);
+        assert_eq!(steps("ab", "ab"), [Edit::Keep, Edit::Keep]);
+        assert_eq!(steps("ab", "axb"), [Edit::Keep, Edit::Insert, Edit::Keep]);
+        assert_eq!(steps("axb", "ab"), [Edit::Keep, Edit::Delete, Edit::Keep]);
+        assert_eq!(steps("ab", "xb"), [Edit::Substitute, Edit::Keep]);
+        assert_eq!(steps("", "ab"), [Edit::Insert, Edit::Insert]);
+        assert_eq!(
+            steps("kitten", "sitting"),
+            [
+                Edit::Substitute,
+                Edit::Keep,
+                Edit::Keep,
+                Edit::Keep,
+                Edit::Substitute,
+                Edit::Keep,
+                Edit::Insert,
+            ]
+        );
+    }
+
+    #[test]
+    fn typos_count() {
+        let rules = RecallSettings {
+            allowed_typos: Typos::Count(1),
+            ..Default::default()
+        };
+        let typo = Some(Leniency {
+            typos: 1,
+            ..Default::default()
+        });
+        assert_eq!(
+            rules.test_match("receive", "receive"),
+            Some(Leniency::default())
+        );
+        assert_eq!(rules.test_match("receive", "recieve"), typo);
+        assert_eq!(rules.test_match("receive", "receve"), typo);
+        assert_eq!(rules.test_match("receive", "receivee"), typo);
+        assert_eq!(rules.test_match("receive", "recive!"), None);
+    }
+
+    #[test]
+    fn typos_percent() {
+        let rules = RecallSettings {
+            allowed_typos: Typos::Percent(20),
+            ..Default::default()
+        };
+        let matches = |text| rules.test_match("photosynthesis", text).is_some();
+        assert!(rules.test_match("cat", "cot").is_none());
+        assert!(matches("fotosynthesis"));
+        assert!(matches("photosynthesys"));
+        assert!(!matches("fotosynthesys"));
+    }
+
+    #[test]
+    fn typos_prefer_other_leniency() {
+        let rules = RecallSettings {
+            check_accents: false,
+            allowed_typos: Typos::Count(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            rules.test_match("niño", "nino"),
+            Some(Leniency {
+                accents: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn language_ignored_when_checking_caps() {
+        let rules = RecallSettings {
+            check_caps: true,
+            language: Some(Language::new("tr")),
+            ..Default::default()
+        };
+        assert!(rules.test_match("İstanbul", "istanbul").is_none());
+    }
+
+    #[test]
+    fn prefixes_optional() {
+        let rules = RecallSettings {
+            ignore_prefixes: vec!["to".into(), "a".into(), "an".into()],
+            allowed_typos: Typos::Count(1),
+            ..Default::default()
+        };
+        let ignored = Some(Leniency {
+            prefix: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            rules.test_match("to run", "to run"),
+            Some(Leniency::default())
+        );
+        assert_eq!(rules.test_match("to run", "run"), ignored);
+        assert_eq!(rules.test_match("run", "To run"), ignored);
+        assert_eq!(rules.test_match("an apple", "a apple"), ignored);
+        assert_eq!(
+            rules.test_match("to run", "ro run"),
+            Some(Leniency {
+                typos: 1,
+                ..Default::default()
+            })
+        );
+        assert!(rules.test_match("to run", "to walk").is_none());
+        // Only whole leading words are prefixes.
+        assert!(rules.test_match("total", "tal").is_none());
+    }
+
+    #[test]
+    fn prefixes_strict() {
+        let rules = RecallSettings {
+            ignore_prefixes: vec!["der".into(), "die".into(), "das".into()],
+            prefix_mode: PrefixMode::Strict,
+            allowed_typos: Typos::Count(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            rules.test_match("der Hund", "der Hund"),
+            Some(Leniency::default())
+        );
+        assert_eq!(
+            rules.test_match("der Hund", "Der hnud"),
+            Some(Leniency {
+                caps: true,
+                typos: 1,
+                ..Default::default()
+            })
+        );
+        assert!(rules.test_match("der Hund", "die Hund").is_none());
+        assert!(rules.test_match("der Hund", "Hund").is_none());
+        assert!(rules.test_match("Hund", "der Hund").is_none());
+    }
+}
@@ -0,0 +1,193 @@
+use smartstring::alias::String;
+
+/// Most parenthesized parts of an answer that are treated as optional,
+/// counting nested parts; any after this are matched literally.
+const MAX_OPTIONAL_PARTS: usize = 4;
+
+/// An answer with parenthesized parts that can be left out, such as "(to)
+/// run", which accepts "run" and "to run".  Parts can be nested, so "a ((b)
+/// c)" accepts "a", "a c", and "a b c".
+///
+/// The answer is shown with the parentheses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionalAnswer {
+    source: String,
+    /// The answer as shown.
+    text: String,
+    /// The answer with and without each optional part.
+    expansions: Vec<String>,
+}
+
+impl OptionalAnswer {
+    /// Reads `source`, where "\\(" and "\\)" are parentheses that are part of
+    /// the answer and "\\\\" is a backslash.
+    pub fn new(source: &str) -> Self {
+        // Each character along with whether it was escaped.
+        let mut chars = Vec::with_capacity(source.len());
+        let mut iter = source.chars().peekable();
+        while let Some(ch) = iter.next() {
+            match (ch, iter.peek()) {
+                ('\\', Some(&next @ ('(' | ')' | '\\'))) => {
+                    iter.next();
+                    chars.push((next, true));
+                }
+                _ => chars.push((ch, false)),
+            }
+        }
+        let mut remaining = MAX_OPTIONAL_PARTS;
+        let mut expansions = Vec::new();
+        for expansion in self::expansions(&chars, &mut remaining) {
+            if !expansions.contains(&expansion) {
+                expansions.push(expansion);
+            }
+        }
+        Self {
+            source: source.into(),
+            text: chars.iter().map(|c| c.0).collect(),
+            expansions,
+        }
+    }
+
+    /// The answer as written, including escapes.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The answer as shown, with its optional parts in parentheses.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Every way of writing this with or without each of its optional parts.
+    /// Doesn't include [`OptionalAnswer::text`] unless it has no optional
+    /// parts.
+    pub(crate) fn expansions(&self) -> impl Iterator<Item = &str> {
+        self.expansions.iter().map(String::as_str)
+    }
+}
+
+/// A piece of an answer with optional parts.
+enum Piece<'a> {
+    Literal(&'a [(char, bool)]),
+    /// The ways of writing the inside of an optional part.
+    Optional(Vec<String>),
+}
+
+/// Returns every way of writing `chars` with or without each of their
+/// parenthesized parts, which may repeat.  Escaped parentheses don't start or
+/// end parts.  Only the first `remaining` parts, including nested ones, are
+/// optional and the rest are left as they are.
+fn expansions(chars: &[(char, bool)], remaining: &mut usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = 0;
+    let mut open = None;
+    let mut depth = 0_usize;
+    for (index, &(ch, escaped)) in chars.iter().enumerate() {
+        match (ch, escaped) {
+            ('(', false) => {
+                if depth == 0 {
+                    open = Some(index);
+                }
+                depth += 1;
+            }
+            (')', false) if depth > 0 => {
+                depth -= 1;
+                match (depth, open) {
+                    (0, Some(start)) if *remaining > 0 => {
+                        *remaining -= 1;
+                        pieces.push(Piece::Literal(&chars[rest..start]));
+                        let inner = expansions(&chars[start + 1..index], remaining);
+                        pieces.push(Piece::Optional(inner));
+                        rest = index + 1;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    pieces.push(Piece::Literal(&chars[rest..]));
+
+    // Each way of writing the pieces so far, along with whether whitespace
+    // at the start of the next piece should be skipped so an omitted part
+    // doesn't leave two spaces.
+    let mut written = vec![(String::new(), false)];
+    for piece in pieces {
+        written = match piece {
+            Piece::Literal(chars) => written
+                .into_iter()
+                .map(|(mut buf, skip_whitespace)| {
+                    let mut chars = chars.iter().map(|c| c.0).peekable();
+                    if skip_whitespace {
+                        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                    }
+                    buf.extend(chars);
+                    (buf, false)
+                })
+                .collect(),
+            Piece::Optional(inner) => written
+                .into_iter()
+                .flat_map(|(buf, skip_whitespace)| {
+                    let skip_after = buf.chars().next_back().is_none_or(char::is_whitespace);
+                    let omitted = (buf.clone(), skip_whitespace || skip_after);
+                    let included = inner.iter().map(move |part| {
+                        let mut buf = buf.clone();
+                        buf.push_str(part);
+                        (buf, skip_whitespace && part.is_empty())
+                    });
+                    std::iter::once(omitted).chain(included)
+                })
+                .collect(),
+        };
+    }
+    written.into_iter().map(|(buf, _)| buf).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expansions(source: &str) -> Vec<std::string::String> {
+        OptionalAnswer::new(source)
+            .expansions()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn expansions_test() {
+        assert_eq!(expansions("run"), ["run"]);
+        assert_eq!(expansions("(to) run"), ["run", "to run"]);
+        assert_eq!(
+            expansions("the (big) house"),
+            ["the house", "the big house"]
+        );
+        assert_eq!(expansions("colo(u)r"), ["color", "colour"]);
+        assert_eq!(expansions("(a) b (c)"), ["b ", "b c", "a b ", "a b c"]);
+        assert_eq!(expansions("a ((b) c)"), ["a ", "a c", "a b c"]);
+        assert_eq!(expansions("(a (b))c"), ["c", "a c", "a bc"]);
+        assert_eq!(expansions("a (b"), ["a (b"]);
+        assert_eq!(expansions("a) b"), ["a) b"]);
+    }
+
+    #[test]
+    fn escaped_parentheses() {
+        assert_eq!(expansions(r"f\(x\)"), ["f(x)"]);
+        assert_eq!(expansions(r"(the) f\(x\)"), ["f(x)", "the f(x)"]);
+        assert_eq!(expansions(r"a\\ (b)"), [r"a\ ", r"a\ b"]);
+        let answer = OptionalAnswer::new(r"\(to\) run");
+        assert_eq!(answer.text(), "(to) run");
+        assert_eq!(answer.source(), r"\(to\) run");
+    }
+
+    #[test]
+    fn expansions_capped() {
+        let answer = OptionalAnswer::new("(a)(b)(c)(d)(e)(f)");
+        assert_eq!(answer.expansions().count(), 1 << MAX_OPTIONAL_PARTS);
+        assert!(answer.expansions().all(|text| text.ends_with("(e)(f)")));
+        // Nested parts count towards the limit.
+        let answer = OptionalAnswer::new("((a)(b))(c)(d)");
+        assert!(answer.expansions().all(|text| text.ends_with("(d)")));
+        assert!(answer.expansions().any(|text| text == "ab(d)"));
+    }
+}
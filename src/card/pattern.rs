@@ -0,0 +1,199 @@
+use regex::Regex;
+use regex_syntax::{
+    hir::{Class, Hir, HirKind, Look},
+    ParserBuilder,
+};
+use smartstring::alias::String;
+
+/// A regular expression an answer can match instead of fixed text, such as
+/// "colou?r".
+///
+/// The whole answer must match the pattern.
+#[derive(Debug, Clone)]
+pub struct AnswerPattern {
+    source: String,
+    canonical: String,
+    regex: Regex,
+    regex_ignore_caps: Regex,
+}
+
+impl AnswerPattern {
+    /// Compiles `source` into a pattern.
+    pub fn new(source: &str) -> Result<Self, regex::Error> {
+        let hir = parse(source, false)?;
+        let mut canonical = String::new();
+        push_canonical(&hir, &mut canonical);
+        let regex = anchored(hir)?;
+        // Assertions such as `\b` are skipped when building the canonical
+        // text, so it might not match.
+        if !regex.is_match(&canonical) {
+            return Err(regex::Error::Syntax(format!(
+                "no simple text matching {source:?} could be found"
+            )));
+        }
+        Ok(Self {
+            source: source.into(),
+            canonical,
+            regex,
+            regex_ignore_caps: anchored(parse(source, true)?)?,
+        })
+    }
+
+    /// The pattern as written.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Simple text matching this pattern, shown when the pattern has to be
+    /// displayed as text.  It takes the first alternative of every
+    /// alternation and the fewest repetitions, so it is "color" for "colou?r"
+    /// and "first" for "first|1st".
+    pub fn canonical(&self) -> &str {
+        &self.canonical
+    }
+
+    pub(crate) fn is_match(&self, text: &str, ignore_caps: bool) -> bool {
+        if ignore_caps {
+            self.regex_ignore_caps.is_match(text)
+        } else {
+            self.regex.is_match(text)
+        }
+    }
+}
+
+impl PartialEq for AnswerPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+/// Parses `source` on its own, so it can't reach outside the anchors added
+/// by [`anchored`].
+fn parse(source: &str, ignore_caps: bool) -> Result<Hir, regex::Error> {
+    ParserBuilder::new()
+        .case_insensitive(ignore_caps)
+        .build()
+        .parse(source)
+        .map_err(|e| regex::Error::Syntax(e.to_string()))
+}
+
+/// Compiles `hir` so it only matches whole strings.
+fn anchored(hir: Hir) -> Result<Regex, regex::Error> {
+    Regex::new(&Hir::concat(vec![Hir::look(Look::Start), hir, Hir::look(Look::End)]).to_string())
+}
+
+/// How many characters of a class are looked at when picking a printable
+/// one, so huge classes such as `[^a-z]` stay quick.
+const CLASS_SEARCH_LIMIT: usize = 0x1000;
+
+/// Pushes the text matched by taking the first branch of every alternation,
+/// a printable character from every class, and the fewest repetitions.
+fn push_canonical(hir: &Hir, buf: &mut String) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => {
+            buf.push_str(&std::string::String::from_utf8_lossy(&literal.0))
+        }
+        HirKind::Class(Class::Unicode(class)) => {
+            let chars = class
+                .ranges()
+                .iter()
+                .flat_map(|range| range.start()..=range.end());
+            if let Some(ch) = class_member(chars) {
+                buf.push(ch);
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let chars = class
+                .ranges()
+                .iter()
+                .flat_map(|range| range.start()..=range.end())
+                .filter(u8::is_ascii)
+                .map(char::from);
+            if let Some(ch) = class_member(chars) {
+                buf.push(ch);
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            for _ in 0..repetition.min {
+                push_canonical(&repetition.sub, buf);
+            }
+        }
+        HirKind::Capture(capture) => push_canonical(&capture.sub, buf),
+        HirKind::Concat(hirs) => hirs.iter().for_each(|hir| push_canonical(hir, buf)),
+        HirKind::Alternation(hirs) => {
+            if let Some(hir) = hirs.first() {
+                push_canonical(hir, buf);
+            }
+        }
+    }
+}
+
+/// The first letter or digit in `chars`, or else the first character that
+/// isn't whitespace or a control character, or else the first character.
+fn class_member(chars: impl Iterator<Item = char>) -> Option<char> {
+    let (mut printable, mut first) = (None, None);
+    for ch in chars.take(CLASS_SEARCH_LIMIT) {
+        if ch.is_alphanumeric() {
+            return Some(ch);
+        }
+        if printable.is_none() && !ch.is_whitespace() && !ch.is_control() {
+            printable = Some(ch);
+        }
+        first.get_or_insert(ch);
+    }
+    printable.or(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_matches() {
+        let pattern = AnswerPattern::new("colou?r").unwrap();
+        assert!(pattern.is_match("color", false));
+        assert!(pattern.is_match("colour", false));
+        assert!(!pattern.is_match("Colour", false));
+        assert!(pattern.is_match("Colour", true));
+        assert!(!pattern.is_match("colors", true));
+        assert!(!pattern.is_match("my color", true));
+    }
+
+    #[test]
+    fn pattern_canonical() {
+        let canonical = |source| AnswerPattern::new(source).unwrap().canonical.clone();
+        assert_eq!(canonical("colou?r"), "color");
+        assert_eq!(canonical("1st|first"), "1st");
+        assert_eq!(canonical("first|1st"), "first");
+        assert_eq!(canonical("(the )?cats?"), "cat");
+        assert_eq!(canonical("[A-Z]{3}"), "AAA");
+        assert_eq!(canonical(r"\d+ BCE"), "0 BCE");
+        assert_eq!(canonical("a.c"), "a0c");
+        assert_eq!(canonical("[^x]y"), "0y");
+        assert_eq!(canonical("[-.]"), "-");
+    }
+
+    #[test]
+    fn pattern_invalid() {
+        assert!(AnswerPattern::new("colou?r(").is_err());
+        assert!(AnswerPattern::new(r"a\bb").is_err());
+        // The source can't close the group around it.
+        assert!(AnswerPattern::new("x)|(y").is_err());
+    }
+
+    #[test]
+    fn pattern_anchored() {
+        // Comments can't hide the anchors.
+        let pattern = AnswerPattern::new("(?x)a # comment").unwrap();
+        assert!(pattern.is_match("a", false));
+        assert!(!pattern.is_match("ab", false));
+        let pattern = AnswerPattern::new("a|ab").unwrap();
+        assert!(pattern.is_match("ab", false));
+        assert!(!pattern.is_match("abc", true));
+        // Flags in the source still apply when caps are ignored.
+        let pattern = AnswerPattern::new("(?-i:a)b").unwrap();
+        assert!(pattern.is_match("aB", true));
+        assert!(!pattern.is_match("AB", true));
+    }
+}
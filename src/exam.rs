@@ -0,0 +1,473 @@
+use std::fmt::{self, Display};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    card::Set,
+    question::{AnswerVerdict, Conditions, McList, Question},
+};
+
+mod export;
+
+pub use export::ExportFormat;
+
+/// A test made of a fixed mix of questions from a [`Set`], in one or more
+/// variants that ask the same questions in different orders.
+///
+/// Exams are generated from a seed with a portable random number generator,
+/// so the same exam can be generated again from the same set, settings, and
+/// seed on any platform.  Exams from the same seed may change in new minor
+/// versions of this library.
+#[derive(Debug, Clone)]
+pub struct Exam<'a> {
+    seed: u64,
+    variants: Vec<Variant<'a>>,
+}
+
+/// How many of each kind of question an [`Exam`] has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExamSettings {
+    /// Multiple choice questions from the set's multiple choice cards.
+    pub mc: usize,
+    /// Typed questions showing the front of a flashcard and asking for the
+    /// back.
+    pub recall_back: usize,
+    /// Typed questions showing the back of a flashcard and asking for the
+    /// front.
+    pub recall_front: usize,
+    /// How many choices multiple choice questions have.
+    pub mc_choices: usize,
+    /// How many variants to generate.
+    pub variants: usize,
+}
+
+impl Default for ExamSettings {
+    fn default() -> Self {
+        Self {
+            mc: 0,
+            recall_back: 0,
+            recall_front: 0,
+            mc_choices: 4,
+            variants: 1,
+        }
+    }
+}
+
+/// The kind of an exam question.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section {
+    /// Multiple choice question from a multiple choice card.
+    Mc,
+    /// Typed question asking for the back of a flashcard.
+    RecallBack,
+    /// Typed question asking for the front of a flashcard.
+    RecallFront,
+}
+
+impl Section {
+    const ALL: [Section; 3] = [Section::Mc, Section::RecallBack, Section::RecallFront];
+
+    fn conditions(self) -> Conditions {
+        match self {
+            Section::Mc => Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            },
+            Section::RecallBack => Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            },
+            Section::RecallFront => Conditions {
+                include_card_front: true,
+                ..Conditions::INCLUDE_NONE
+            },
+        }
+    }
+}
+
+/// One ordering of the questions on an [`Exam`].
+#[derive(Debug, Clone)]
+pub struct Variant<'a> {
+    items: Vec<ExamItem<'a>>,
+}
+
+/// A question on an [`Exam`].
+#[derive(Debug, Clone)]
+pub struct ExamItem<'a> {
+    pub question: Question<'a>,
+    pub section: Section,
+    /// The text shown to the student.
+    pub prompt: &'a str,
+    /// Choices to pick the answer from, for multiple choice questions.
+    pub choices: Option<McList<'a>>,
+}
+
+/// Why an [`Exam`] couldn't be generated or graded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExamError {
+    /// The set doesn't have enough questions of a kind.
+    NotEnoughQuestions {
+        section: Section,
+        requested: usize,
+        available: usize,
+    },
+    /// There are fewer orders of the questions than variants requested.
+    TooManyVariants { requested: usize, possible: usize },
+    /// An answer sheet doesn't have one answer for each question.
+    AnswerCount { expected: usize, given: usize },
+}
+
+impl Display for ExamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExamError::NotEnoughQuestions {
+                section,
+                requested,
+                available,
+            } => write!(
+                f,
+                "Requested {requested} {section:?} questions but only {available} are available"
+            ),
+            ExamError::TooManyVariants {
+                requested,
+                possible,
+            } => write!(
+                f,
+                "Requested {requested} variants but questions can only be ordered {possible} ways"
+            ),
+            ExamError::AnswerCount { expected, given } => {
+                write!(f, "Expected {expected} answers but got {given}")
+            }
+        }
+    }
+}
+
+impl<'a> Exam<'a> {
+    /// Generates an exam from `set` with the mix of questions in `settings`.
+    ///
+    /// Each variant asks the same questions with the same choices, and no two
+    /// variants ask them in the same order.
+    pub fn generate(set: &'a Set, settings: &ExamSettings, seed: u64) -> Result<Self, ExamError> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut items = Vec::new();
+        for section in Section::ALL {
+            let requested = match section {
+                Section::Mc => settings.mc,
+                Section::RecallBack => settings.recall_back,
+                Section::RecallFront => settings.recall_front,
+            };
+            let mut questions = set.questions(section.conditions()).collect::<Vec<_>>();
+            questions.shuffle(&mut rng);
+            let len = items.len();
+            items.extend(
+                questions
+                    .into_iter()
+                    .filter_map(|question| {
+                        let prompt = question.question(&mut rng)?;
+                        let choices = match section {
+                            Section::Mc => {
+                                Some(question.mc_answers(settings.mc_choices, &mut rng)?)
+                            }
+                            Section::RecallBack | Section::RecallFront => None,
+                        };
+                        Some(ExamItem {
+                            question,
+                            section,
+                            prompt,
+                            choices,
+                        })
+                    })
+                    .take(requested),
+            );
+            let available = items.len() - len;
+            if available < requested {
+                return Err(ExamError::NotEnoughQuestions {
+                    section,
+                    requested,
+                    available,
+                });
+            }
+        }
+
+        let possible = (1..=items.len()).try_fold(1usize, usize::checked_mul);
+        if let Some(possible) = possible.filter(|&possible| possible < settings.variants) {
+            return Err(ExamError::TooManyVariants {
+                requested: settings.variants,
+                possible,
+            });
+        }
+        let mut orders = Vec::<Vec<usize>>::with_capacity(settings.variants);
+        while orders.len() < settings.variants {
+            let mut order = (0..items.len()).collect::<Vec<_>>();
+            order.shuffle(&mut rng);
+            if !orders.contains(&order) {
+                orders.push(order);
+            }
+        }
+
+        let variants = orders
+            .into_iter()
+            .map(|order| Variant {
+                items: order.into_iter().map(|i| items[i].clone()).collect(),
+            })
+            .collect();
+        Ok(Self { seed, variants })
+    }
+
+    /// The seed this exam was generated from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Each variant of this exam.
+    pub fn variants(&self) -> &[Variant<'a>] {
+        &self.variants
+    }
+}
+
+impl<'a> Variant<'a> {
+    /// The questions in this variant, in the order they are asked.
+    pub fn items(&self) -> &[ExamItem<'a>] {
+        &self.items
+    }
+
+    /// Grades an answer sheet with one answer for each question in this
+    /// variant, in order.  Leave questions that weren't answered blank.
+    ///
+    /// Answers to multiple choice questions should be the letter of the chosen
+    /// choice, as labeled by [`Variant::student_copy`].
+    pub fn grade<S: AsRef<str>>(&self, answers: &[S]) -> Result<ScoreReport<'a>, ExamError> {
+        if answers.len() != self.items.len() {
+            return Err(ExamError::AnswerCount {
+                expected: self.items.len(),
+                given: answers.len(),
+            });
+        }
+        let results = self
+            .items
+            .iter()
+            .zip(answers)
+            .map(|(item, answer)| {
+                if let Some(choices) = &item.choices {
+                    let correct = export::choice_index(answer.as_ref())
+                        .and_then(|index| choices.select(index))
+                        .unwrap_or(false);
+                    return ItemResult {
+                        section: item.section,
+                        correct,
+                        verdict: None,
+                        points: if correct { 1.0 } else { 0.0 },
+                    };
+                }
+                let verdict = item.question.check_answer(answer.as_ref());
+                let points = match &verdict {
+                    _ if verdict.is_correct() => 1.0,
+                    AnswerVerdict::Partial { credit, .. } if credit.required > 0 => {
+                        credit
+                            .given
+                            .min(credit.required)
+                            .saturating_sub(credit.wrong) as f64
+                            / credit.required as f64
+                    }
+                    _ => 0.0,
+                };
+                ItemResult {
+                    section: item.section,
+                    correct: verdict.is_correct(),
+                    verdict: Some(verdict),
+                    points,
+                }
+            })
+            .collect();
+        Ok(ScoreReport { results })
+    }
+}
+
+/// The graded answers on an answer sheet.
+///
+/// Created by [`Variant::grade`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreReport<'a> {
+    /// The result of each question, in the order they were asked.
+    pub results: Vec<ItemResult<'a>>,
+}
+
+/// The grade of the answer to one exam question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemResult<'a> {
+    pub section: Section,
+    pub correct: bool,
+    /// How correct a typed answer was, or `None` for multiple choice
+    /// questions.
+    pub verdict: Option<AnswerVerdict<'a>>,
+    /// 1 for correct answers, 0 for wrong ones, and the fraction of the list
+    /// given for partially correct list answers.
+    pub points: f64,
+}
+
+impl<'a> ScoreReport<'a> {
+    /// Total points scored.
+    pub fn score(&self) -> f64 {
+        self.results.iter().map(|result| result.points).sum()
+    }
+
+    /// Points that could have been scored.
+    pub fn max_score(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Points scored and points that could have been scored in one section.
+    pub fn section_score(&self, section: Section) -> (f64, usize) {
+        self.results
+            .iter()
+            .filter(|result| result.section == section)
+            .fold((0.0, 0), |(score, max), result| {
+                (score + result.points, max + 1)
+            })
+    }
+
+    /// Fraction of points scored, or `None` if the exam had no questions.
+    pub fn ratio(&self) -> Option<f64> {
+        (self.max_score() > 0).then(|| self.score() / self.max_score() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mc: usize, recall_back: usize, recall_front: usize) -> ExamSettings {
+        ExamSettings {
+            mc,
+            recall_back,
+            recall_front,
+            ..ExamSettings::default()
+        }
+    }
+
+    #[test]
+    fn exam_composition() {
+        let set = Set::example_recall_default();
+        let exam = Exam::generate(&set, &settings(2, 3, 1), 7).unwrap();
+        let items = exam.variants()[0].items();
+        assert_eq!(items.len(), 6);
+        let count = |section| items.iter().filter(|item| item.section == section).count();
+        assert_eq!(count(Section::Mc), 2);
+        assert_eq!(count(Section::RecallBack), 3);
+        assert_eq!(count(Section::RecallFront), 1);
+        for item in items {
+            assert_eq!(item.choices.is_some(), item.section == Section::Mc);
+        }
+        assert_eq!(
+            Exam::generate(&set, &settings(5, 0, 0), 7).unwrap_err(),
+            ExamError::NotEnoughQuestions {
+                section: Section::Mc,
+                requested: 5,
+                available: 4
+            }
+        );
+    }
+
+    #[test]
+    fn exam_reproducible() {
+        let set = Set::example_recall_default();
+        let prompts = |seed| {
+            Exam::generate(&set, &settings(2, 2, 2), seed)
+                .unwrap()
+                .variants()[0]
+                .items()
+                .iter()
+                .map(|item| item.prompt)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(prompts(3), prompts(3));
+        assert_ne!(prompts(3), prompts(4));
+        // The generator is portable, so seeds give the same exam everywhere.
+        assert_eq!(prompts(3), ["f", "a", "1mc", "2mc", "1", "2"]);
+    }
+
+    #[test]
+    fn exam_variants() {
+        let set = Set::example_recall_default();
+        let settings = ExamSettings {
+            variants: 6,
+            ..settings(1, 1, 1)
+        };
+        let exam = Exam::generate(&set, &settings, 0).unwrap();
+        let orders = exam
+            .variants()
+            .iter()
+            .map(|variant| variant.items().iter().map(|item| item.prompt).collect())
+            .collect::<Vec<Vec<_>>>();
+        assert_eq!(orders.len(), 6);
+        for (i, order) in orders.iter().enumerate() {
+            assert!(!orders[..i].contains(order));
+            let mut sorted = order.clone();
+            sorted.sort();
+            let mut first = orders[0].clone();
+            first.sort();
+            assert_eq!(sorted, first);
+        }
+
+        let settings = ExamSettings {
+            variants: 7,
+            ..settings
+        };
+        assert_eq!(
+            Exam::generate(&set, &settings, 0).unwrap_err(),
+            ExamError::TooManyVariants {
+                requested: 7,
+                possible: 6
+            }
+        );
+    }
+
+    #[test]
+    fn exam_grading() {
+        let set = Set::example_recall_default();
+        let exam = Exam::generate(&set, &settings(2, 2, 0), 1).unwrap();
+        let variant = &exam.variants()[0];
+        let answers = variant
+            .items()
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match (&item.choices, i) {
+                (_, 0) => String::new(),
+                (Some(choices), _) => export::choice_label(choices.correct_index()),
+                (None, _) => item
+                    .question
+                    .answers()
+                    .next()
+                    .expect("Flashcard should have text")
+                    .to_owned(),
+            })
+            .collect::<Vec<_>>();
+        let report = variant.grade(&answers).unwrap();
+        assert_eq!(report.max_score(), 4);
+        assert_eq!(report.score(), 3.0);
+        assert_eq!(report.ratio(), Some(0.75));
+        assert!(!report.results[0].correct);
+        let mc = variant
+            .items()
+            .iter()
+            .position(|item| item.choices.is_some())
+            .unwrap();
+        let mut wrong = answers.clone();
+        let choices = variant.items()[mc].choices.as_ref().unwrap();
+        wrong[mc] = export::choice_label((choices.correct_index() + 1) % choices.choice_count());
+        assert!(!variant.grade(&wrong).unwrap().results[mc].correct);
+        wrong[mc] = choices.correct().text().to_owned();
+        assert!(!variant.grade(&wrong).unwrap().results[mc].correct);
+        let (score, max) = report.section_score(variant.items()[0].section);
+        assert_eq!((score, max), (1.0, 2));
+        assert_eq!(
+            variant.grade(&answers[1..]).unwrap_err(),
+            ExamError::AnswerCount {
+                expected: 4,
+                given: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::fmt::Write;
+
+use super::{ExamItem, Variant};
+
+/// A printable document format an exam can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    /// A standalone HTML page with no external resources.
+    Html,
+}
+
+const WRITE: &str = "Writing to string can't fail";
+
+/// Styles for HTML exports, kept simple so pages print well.
+const HTML_STYLE: &str = "body { font-family: serif; max-width: 45em; margin: 2em auto; }
+li { margin-bottom: 1em; }
+ol.choices { list-style-type: upper-alpha; }
+.blank { border-bottom: 1px solid black; height: 1.5em; }";
+
+impl<'a> Variant<'a> {
+    /// The copy of this variant given to students, with each question and the
+    /// choices for multiple choice questions.
+    pub fn student_copy(&self, title: &str, format: ExportFormat) -> String {
+        let mut buf = String::new();
+        match format {
+            ExportFormat::Markdown => {
+                writeln!(buf, "# {}", escape_markdown(title, "")).expect(WRITE);
+                for (i, item) in self.items.iter().enumerate() {
+                    write_markdown_prompt(&mut buf, i, item);
+                    match &item.choices {
+                        Some(choices) => {
+                            for (j, choice) in choices.choices().enumerate() {
+                                writeln!(
+                                    buf,
+                                    "   - {}. {}",
+                                    choice_label(j),
+                                    escape_markdown(choice.text(), "     ")
+                                )
+                                .expect(WRITE);
+                            }
+                        }
+                        None => buf.push_str("   Answer: ________________\n"),
+                    }
+                }
+            }
+            ExportFormat::Html => {
+                write_html_start(&mut buf, title);
+                for item in &self.items {
+                    writeln!(buf, "<li><p>{}</p>", escape_html(item.prompt)).expect(WRITE);
+                    match &item.choices {
+                        Some(choices) => {
+                            buf.push_str("<ol class=\"choices\">\n");
+                            for choice in choices.choices() {
+                                writeln!(buf, "<li>{}</li>", escape_html(choice.text()))
+                                    .expect(WRITE);
+                            }
+                            buf.push_str("</ol>\n");
+                        }
+                        None => buf.push_str("<div class=\"blank\"></div>\n"),
+                    }
+                    buf.push_str("</li>\n");
+                }
+                write_html_end(&mut buf);
+            }
+        }
+        buf
+    }
+
+    /// The answer key for this variant, with the letter of the correct choice
+    /// for multiple choice questions and every accepted answer for typed
+    /// questions.
+    pub fn answer_key(&self, title: &str, format: ExportFormat) -> String {
+        let title = format!("{title} (answer key)");
+        let mut buf = String::new();
+        match format {
+            ExportFormat::Markdown => {
+                writeln!(buf, "# {}", escape_markdown(&title, "")).expect(WRITE);
+                for (i, item) in self.items.iter().enumerate() {
+                    write_markdown_prompt(&mut buf, i, item);
+                    let answer = match &item.choices {
+                        Some(choices) => format!(
+                            "{}. {}",
+                            choice_label(choices.correct_index()),
+                            escape_markdown(choices.correct().text(), "     ")
+                        ),
+                        None => item
+                            .question
+                            .answers()
+                            .map(|answer| escape_markdown(answer, "     "))
+                            .collect::<Vec<_>>()
+                            .join(" / "),
+                    };
+                    writeln!(buf, "   - **{answer}**").expect(WRITE);
+                }
+            }
+            ExportFormat::Html => {
+                write_html_start(&mut buf, &title);
+                for item in &self.items {
+                    writeln!(buf, "<li><p>{}</p>", escape_html(item.prompt)).expect(WRITE);
+                    let answer = match &item.choices {
+                        Some(choices) => format!(
+                            "{}. {}",
+                            choice_label(choices.correct_index()),
+                            escape_html(choices.correct().text())
+                        ),
+                        None => item
+                            .question
+                            .answers()
+                            .map(escape_html)
+                            .collect::<Vec<_>>()
+                            .join(" / "),
+                    };
+                    writeln!(buf, "<p><strong>{answer}</strong></p></li>").expect(WRITE);
+                }
+                write_html_end(&mut buf);
+            }
+        }
+        buf
+    }
+}
+
+fn write_markdown_prompt(buf: &mut String, index: usize, item: &ExamItem<'_>) {
+    write!(
+        buf,
+        "\n{}. {}\n\n",
+        index + 1,
+        escape_markdown(item.prompt, "   ")
+    )
+    .expect(WRITE);
+}
+
+fn write_html_start(buf: &mut String, title: &str) {
+    let title = escape_html(title);
+    write!(
+        buf,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n<ol>\n"
+    )
+    .expect(WRITE);
+}
+
+fn write_html_end(buf: &mut String) {
+    buf.push_str("</ol>\n</body>\n</html>\n");
+}
+
+/// Label of the choice at `index`: A to Z, then AA, AB, and so on.
+pub(super) fn choice_label(mut index: usize) -> String {
+    let mut label = Vec::new();
+    loop {
+        label.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    label.reverse();
+    String::from_utf8(label).expect("Labels should be ASCII")
+}
+
+/// Index of the choice with `label`, ignoring case and surrounding whitespace.
+pub(super) fn choice_index(label: &str) -> Option<usize> {
+    let label = label.trim();
+    if label.is_empty() {
+        return None;
+    }
+    label
+        .chars()
+        .try_fold(0usize, |index, c| {
+            let digit = c.to_ascii_uppercase();
+            if !digit.is_ascii_uppercase() {
+                return None;
+            }
+            index
+                .checked_mul(26)?
+                .checked_add(digit as usize - 'A' as usize + 1)
+        })
+        .map(|index| index - 1)
+}
+
+/// Escapes characters Markdown would treat as formatting, and continues lines
+/// after newlines with `indent` so they stay in the same list item.
+fn escape_markdown(text: &str, indent: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => {
+                escaped.push_str("\\\n");
+                escaped.push_str(indent);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes characters HTML would treat as markup, and turns newlines into line
+/// breaks.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '\n' => escaped.push_str("<br>"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::{CardSide, Flashcard, McCard, Set},
+        exam::{Exam, ExamSettings},
+    };
+
+    use super::*;
+
+    fn example_set() -> Set {
+        let mut set = Set::example_recall_default();
+        set.flashcards = vec![Flashcard {
+            front: CardSide::new("a < b"),
+            back: CardSide::new_multi(["yes", "*y*"]),
+            tags: Vec::new(),
+            id_text: None,
+        }];
+        set.mc_cards = vec![McCard {
+            question: CardSide::new("q"),
+            answer: CardSide::new("right"),
+            decoys: ["wrong"].into_iter().collect(),
+            id_text: None,
+        }];
+        set
+    }
+
+    fn example_variant(set: &Set) -> Variant<'_> {
+        let settings = ExamSettings {
+            mc: 1,
+            recall_back: 1,
+            ..ExamSettings::default()
+        };
+        let exam = Exam::generate(set, &settings, 0).unwrap();
+        exam.variants()[0].clone()
+    }
+
+    #[test]
+    fn choice_labels() {
+        assert_eq!(choice_label(0), "A");
+        assert_eq!(choice_label(25), "Z");
+        assert_eq!(choice_label(26), "AA");
+        assert_eq!(choice_label(27), "AB");
+        assert_eq!(choice_label(26 * 27), "AAA");
+        for index in [0, 25, 26, 27, 26 * 27, 1000] {
+            assert_eq!(choice_index(&choice_label(index)), Some(index));
+        }
+        assert_eq!(choice_index(" b "), Some(1));
+        assert_eq!(choice_index(""), None);
+        assert_eq!(choice_index("B."), None);
+    }
+
+    #[test]
+    fn export_markdown() {
+        let set = example_set();
+        let variant = example_variant(&set);
+        let mc = variant.items()[0].choices.is_some();
+        let index = |i: usize| if mc { i } else { 1 - i };
+        let choices = variant.items()[index(0)].choices.as_ref().unwrap();
+
+        let student = variant.student_copy("Quiz", ExportFormat::Markdown);
+        assert!(student.starts_with("# Quiz\n"));
+        assert!(student.contains(&format!(
+            "{}. q\n\n   - A. {}\n",
+            index(0) + 1,
+            choices.choice(0).unwrap().text()
+        )));
+        assert!(student.contains(&format!("{}. a \\< b\n\n   Answer:", index(1) + 1)));
+        assert!(!student.contains("yes"));
+
+        let key = variant.answer_key("Quiz", ExportFormat::Markdown);
+        assert!(key.starts_with("# Quiz (answer key)\n"));
+        assert!(key.contains(&format!(
+            "   - **{}. right**\n",
+            choice_label(choices.correct_index())
+        )));
+        assert!(key.contains("   - **yes / \\*y\\***\n"));
+    }
+
+    #[test]
+    fn export_html() {
+        let set = example_set();
+        let variant = example_variant(&set);
+
+        let student = variant.student_copy("Q & A", ExportFormat::Html);
+        assert!(student.starts_with("<!DOCTYPE html>"));
+        assert!(student.contains("<title>Q &amp; A</title>"));
+        assert!(student.contains("<li><p>a &lt; b</p>\n<div class=\"blank\"></div>\n</li>"));
+        assert!(student.contains("<li>right</li>"));
+        assert!(student.ends_with("</html>\n"));
+
+        let key = variant.answer_key("Q & A", ExportFormat::Html);
+        assert!(key.contains("<h1>Q &amp; A (answer key)</h1>"));
+        assert!(key.contains("<p><strong>yes / *y*</strong></p></li>"));
+    }
+
+    #[test]
+    fn escape_newlines() {
+        assert_eq!(escape_markdown("a\nb_", "  "), "a\\\n  b\\_");
+        assert_eq!(escape_html("a\nb"), "a<br>b");
+    }
+}
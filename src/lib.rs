@@ -9,20 +9,77 @@
 //! specified by adding "@[card front]" "@[card back]" or "@\[mc\]" blocks.  The
 //! recall property may be set to never, text, or multiple choice, defaulting
 //! to multiple choice if unspecified.  The check caps property may be set to
-//! true or false, defaulting to false.  Behavior when properties are repeated
-//! is unspecified.
+//! true or false, defaulting to false.  The check accents property may be set
+//! to true or false, defaulting to true; when false answers missing accents or
+//! other diacritics are still accepted.  The collapse whitespace property may
+//! be set to true or false, defaulting to false; when true runs of whitespace
+//! inside answers count as a single space.  The ignore punctuation property
+//! may be set to none, all, or a list of punctuation characters such as ". -",
+//! defaulting to none.  The allowed typos property may be set to a number of
+//! typos such as 1 or a percentage of the length of the answer such as 20%,
+//! defaulting to 0.  The language property may be set to a
+//! language tag such as "tr" or "de-AT" to use that language's rules when
+//! ignoring capitalization.  The normalization property may be set to nfc or
+//! nfkc, defaulting to nfc, and picks the Unicode normalization form answers
+//! are compared in.  The list separator property may be set to a single
+//! character, defaulting to ",", and separates the items of list answers.
+//! The ignore prefixes property may be set to a comma separated list of
+//! leading words such as "to, a, an".  The prefix mode property may be set to
+//! optional or strict, defaulting to optional; when optional those words may
+//! be left out of answers or be wrong, though the answer then doesn't count as
+//! exact, and when strict they must be given and match the
+//! card exactly, as with German articles.  The decoys property may be set to
+//! random or similar, defaulting to random; when similar multiple choice
+//! questions get the decoys most like the correct answer.  The borrow decoys
+//! property may be set to true or false, defaulting to false; when true
+//! multiple choice cards without enough decoys also use the answers to other
+//! multiple choice cards.  The none of the above and all of the above
+//! properties may be set to a percent chance such as 25%, defaulting to 0%, of
+//! multiple choice cards getting a "None of the above" or, for cards with more
+//! than one answer, an "All of the above" choice.
+//! Behavior when properties are repeated is unspecified.
 //!
 //! Flashcard blocks are defined by a line starting with "\[card\]".  Any lines
 //! below that starting with "F:" are used for the front of the card (so "F:
 //! same") creates a card front with the text "same"; same for "B:" lines for
-//! the back.
+//! the back.  "T:" lines are comma separated tags such as "T: capitals,
+//! europe", used to pick similar decoys.
 //!
 //! Multiple choice blocks are defined by a line starting with "\[mc]\".  "Q:"
 //! lines are for questions, "A:" lines are for answers, and "D:"" lines are
 //! for decoys.
 //!
+//! Progress is kept for each card by its first "F:" or "Q:" line, so other
+//! lines can be edited without losing it.  Either kind of block may have an
+//! "ID:" line, such as "ID: run", to keep progress by that instead, which
+//! allows editing the first line and tells apart cards that start the same.
+//! Loading fails if two cards of the same kind would share progress.
+//!
+//! "F/:", "B/:", and "A/:" lines are answer patterns: regular expressions the
+//! whole answer must match, such as "B/: colou?r".  They are shown as simple
+//! text they match, taking the first alternative and the fewest repetitions
+//! ("color").  Answer patterns are not unescaped.
+//!
+//! "F list:", "B list:", and "A list:" lines are list answers whose items may
+//! be given in any order, such as "B list: red, blue, yellow".  Adding a count,
+//! as in "B list 2:", only requires that many of the items; the count must be
+//! between 1 and the number of items, split by the list separator set before
+//! the card.  Answers giving fewer items get partial credit.
+//!
+//! "F optional:", "B optional:", and "A optional:" lines are answers whose
+//! parenthesized parts can be left out, so "B optional: (to) run" accepts
+//! "run" and "to run".  Parts can be nested, so "a ((b) c)" accepts "a", "a
+//! c", and "a b c".  They are still shown with the parentheses.  On these
+//! lines "\\(" and "\\)" are parentheses that are always part of the answer.
+//!
 //! Card text supports the following escapes: "\\n" for newline and "\\\\" for
-//! backslash.
+//! backslash.  Card text is normalized to NFC when loaded, or to NFKC for
+//! answers that are compared using NFKC.
 
 pub mod card;
+pub mod exam;
+pub mod progress;
 pub mod question;
+pub mod schedule;
+pub mod session;
+pub mod stats;
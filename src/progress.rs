@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    card::{CardId, Set},
+    question::QuestionKey,
+    schedule::Review,
+};
+
+mod loading;
+mod saving;
+
+/// Every review of each question in a set, kept separately from the set so
+/// players don't need to modify shared set files.
+///
+/// Questions are identified by [`QuestionKey`], which depends only on the
+/// [`CardId`] of their card, so history can be loaded for newer versions of a
+/// set, including ones where cards were edited.  Cards added since have no
+/// history, and history of cards that were deleted can be removed with
+/// [`History::retain_cards`].
+///
+/// The state of each question according to the scheduler used is stored too,
+/// so [`crate::schedule::Schedule::from_progress`] keeps questions due when
+/// they were even if the scheduler's settings change.  Questions without a
+/// state, or with one from a different scheduler, have their state rebuilt by
+/// replaying their reviews.
+///
+/// File format:
+///
+/// The first line must be "EFC3 progress \<version\>".  It may be followed by
+/// a "scheduler:" line with the name of the scheduler the states are from,
+/// such as sm2 or fsrs.  Each question is a block starting with a
+/// "\[question\]" line followed by a "card:" line with the card id in
+/// hexadecimal, a "side:" line with front, back, or mc, optionally a "state:"
+/// line with the scheduler's state, and a "review:" line for each review in
+/// the order they happened.  Reviews are
+/// written as the time in seconds since the Unix epoch followed by again,
+/// hard, good, or easy, then optionally the response time in milliseconds,
+/// then optionally the verdict: correct, accepted, partial, or incorrect,
+/// then optionally how the question was asked: never, mc, or text.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct History {
+    reviews: BTreeMap<QuestionKey, Vec<Review>>,
+    /// Name of the scheduler that saved `states`.
+    scheduler: Option<String>,
+    /// Scheduler state of each question, as written by
+    /// [`crate::schedule::Scheduler::save_state`].
+    states: BTreeMap<QuestionKey, String>,
+}
+
+impl History {
+    /// History with no reviews.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a review of the question with the given key.  Reviews must be
+    /// pushed in the order they happened.
+    pub fn push(&mut self, key: QuestionKey, review: Review) {
+        self.reviews.entry(key).or_default().push(review);
+    }
+
+    /// Every review of the question with the given key, oldest first.
+    pub fn reviews(&self, key: QuestionKey) -> &[Review] {
+        self.reviews.get(&key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Name of the scheduler the saved states are from, if there are any.
+    pub fn scheduler(&self) -> Option<&str> {
+        self.scheduler.as_deref()
+    }
+
+    /// Saved scheduler state of the question with the given key.
+    pub fn state(&self, key: QuestionKey) -> Option<&str> {
+        self.states.get(&key).map(String::as_str)
+    }
+
+    /// Sets the scheduler states are saved from, forgetting states saved from
+    /// a different one.
+    pub(crate) fn set_scheduler(&mut self, name: &str) {
+        if self.scheduler.as_deref() != Some(name) {
+            self.scheduler = Some(name.to_owned());
+            self.states.clear();
+        }
+    }
+
+    /// Saves the scheduler state of the question with the given key.
+    pub(crate) fn set_state(&mut self, key: QuestionKey, state: String) {
+        self.states.insert(key, state);
+    }
+
+    /// Returns an iterator over each question with reviews and its reviews.
+    pub fn iter(&self) -> impl Iterator<Item = (QuestionKey, &[Review])> {
+        self.reviews
+            .iter()
+            .map(|(key, reviews)| (*key, reviews.as_slice()))
+    }
+
+    /// Whether there are no reviews.
+    pub fn is_empty(&self) -> bool {
+        self.reviews.is_empty()
+    }
+
+    /// Removes the history of cards that aren't in `set`, such as cards that
+    /// were deleted since this history was saved.
+    pub fn retain_cards(&mut self, set: &Set) {
+        let ids = set
+            .flashcards
+            .iter()
+            .map(|card| card.id())
+            .chain(set.mc_cards.iter().map(|card| card.id()))
+            .collect::<Vec<CardId>>();
+        self.reviews.retain(|key, _| ids.contains(&key.card));
+        self.states.retain(|key, _| ids.contains(&key.card));
+    }
+}
+
+impl IntoIterator for History {
+    type Item = (QuestionKey, Review);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    /// Each review along with the key of the question reviewed, in the order
+    /// they happened for each question.
+    fn into_iter(self) -> Self::IntoIter {
+        self.reviews
+            .into_iter()
+            .flat_map(|(key, reviews)| reviews.into_iter().map(move |review| (key, review)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl FromIterator<(QuestionKey, Review)> for History {
+    fn from_iter<T: IntoIterator<Item = (QuestionKey, Review)>>(iter: T) -> Self {
+        let mut history = Self::new();
+        for (key, review) in iter {
+            history.push(key, review);
+        }
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::{CardSide, Flashcard, Side},
+        schedule::{Grade, Timestamp},
+    };
+
+    use super::*;
+
+    #[test]
+    fn retain_cards() {
+        let mut set = Set::example_recall_default();
+        let review = Review {
+            at: Timestamp::from_unix_secs(0),
+            grade: Grade::Good,
+            response_time: None,
+            verdict: None,
+            recall: None,
+        };
+        let mut history = History::new();
+        for card in &set.flashcards[..2] {
+            history.push(
+                QuestionKey {
+                    card: card.id(),
+                    side: Some(Side::Back),
+                },
+                review,
+            );
+        }
+        let kept = QuestionKey {
+            card: set.flashcards[0].id(),
+            side: Some(Side::Back),
+        };
+
+        // Editing a card keeps its history, replacing it doesn't.
+        set.flashcards[0].back = CardSide::new_multi(["zero", "0"]);
+        set.flashcards[1] = Flashcard::new("replaced", "1");
+        history.retain_cards(&set);
+        assert_eq!(history.iter().count(), 1);
+        assert_eq!(kept.card, set.flashcards[0].id());
+        assert_eq!(history.reviews(kept), [review]);
+    }
+}
@@ -0,0 +1,266 @@
+use std::{fs::File, io::Read, path::Path, time::Duration};
+
+use nom::{
+    bytes::complete::tag,
+    character::{complete::newline, streaming::not_line_ending},
+    combinator::opt,
+    sequence::{delimited, terminated},
+    Finish, Parser,
+};
+
+use crate::{
+    card::{
+        loading::{property_value, Error, IResult, Result, Span, Version},
+        CardId, RecallType, Side,
+    },
+    question::{QuestionKey, VerdictKind},
+    schedule::{Grade, Review, Timestamp},
+};
+
+use super::History;
+
+impl History {
+    /// Loads history from a file.
+    pub fn load(file: impl AsRef<Path>) -> Result<(Self, Option<Version>)> {
+        Self::load_from_reader(File::open(file)?)
+    }
+
+    /// Constructs history by reading from the given reader.
+    pub fn load_from_reader<R: Read>(mut reader: R) -> Result<(Self, Option<Version>)> {
+        fn inner(s: &str) -> Result<(History, Option<Version>)> {
+            let s = Span::new(s);
+            opt(first_line)
+                .and(body)
+                .map(|(version, history)| (history, version))
+                .parse(s)
+                .map(|(_, ret)| ret)
+                .finish()
+        }
+
+        let mut buf = std::string::String::new();
+        reader.read_to_string(&mut buf)?;
+        buf.push('\n');
+        inner(&buf)
+    }
+}
+
+fn first_line(s: Span<'_>) -> IResult<Span<'_>, Version> {
+    delimited(tag("EFC3 progress "), Version::parse, newline)(s)
+}
+
+fn body(mut s: Span<'_>) -> IResult<Span<'_>, History> {
+    let mut history = History::new();
+    while let Ok((rem, line)) = terminated(not_line_ending::<_, Error>, opt(newline))(s) {
+        s = rem;
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("scheduler:") {
+            history.scheduler = Some(name.trim().to_owned());
+        } else if line == "[question]" {
+            let (rem, question) = question(s)?;
+            s = rem;
+            // Blocks missing a card or side can't be matched to a question.
+            if let (Some(card), Some(side), state, reviews) = question {
+                let key = QuestionKey { card, side };
+                history.reviews.entry(key).or_default().extend(reviews);
+                if let Some(state) = state {
+                    history.states.insert(key, state);
+                }
+            }
+        }
+    }
+    Ok((s, history))
+}
+
+type QuestionBlock = (
+    Option<CardId>,
+    Option<Option<Side>>,
+    Option<std::string::String>,
+    Vec<Review>,
+);
+
+fn question(mut s: Span<'_>) -> IResult<Span<'_>, QuestionBlock> {
+    let (mut card, mut side, mut state, mut reviews) = (None, None, None, Vec::new());
+    while let Ok((rem, (property, value))) = property_value(s) {
+        s = rem;
+        let value = value.trim();
+        let invalid = |expected| {
+            nom::Err::Failure(Error::InvalidType {
+                line: property.location_line(),
+                expected,
+            })
+        };
+        match property.trim() {
+            "card" => {
+                card = Some(
+                    u64::from_str_radix(value, 16)
+                        .map(CardId::from_u64)
+                        .map_err(|_| invalid("hexadecimal card id"))?,
+                )
+            }
+            "side" => {
+                side = Some(match value {
+                    "front" => Some(Side::Front),
+                    "back" => Some(Side::Back),
+                    "mc" => None,
+                    _ => return Err(invalid("{ front | back | mc }")),
+                })
+            }
+            // States are checked by the scheduler when they are used.
+            "state" => state = Some(value.to_owned()),
+            "review" => reviews.push(review(value).ok_or(invalid(Review::EXPECTED_VALUES))?),
+            _ => {}
+        }
+    }
+    Ok((s, (card, side, state, reviews)))
+}
+
+impl Review {
+    const EXPECTED_VALUES: &str = "<timestamp> { again | hard | good | easy } \
+        [<response time in milliseconds>] [{ correct | accepted | partial | incorrect }] \
+        [{ never | mc | text }]";
+}
+
+/// Reads a review written as "1700000000 good", "1700000000 good 2500", or
+/// "1700000000 good 2500 correct text".
+fn review(s: &str) -> Option<Review> {
+    let mut parts = s.split_whitespace().peekable();
+    let at = Timestamp::from_unix_secs(parts.next()?.parse().ok()?);
+    let grade = Grade::from_str(parts.next()?)?;
+    let response_time = parts
+        .next_if(|part| part.starts_with(|ch: char| ch.is_ascii_digit()))
+        .map(|millis| millis.parse().ok().map(Duration::from_millis))
+        .map_or(Some(None), |millis| millis.map(Some))?;
+    let verdict = parts
+        .next_if(|part| VerdictKind::from_str(part).is_some())
+        .and_then(VerdictKind::from_str);
+    let recall = match parts.next() {
+        Some(recall) => Some(recall_from_str(recall)?),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Review {
+        at,
+        grade,
+        response_time,
+        verdict,
+        recall,
+    })
+}
+
+impl Grade {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "again" => Some(Self::Again),
+            "hard" => Some(Self::Hard),
+            "good" => Some(Self::Good),
+            "easy" => Some(Self::Easy),
+            _ => None,
+        }
+    }
+}
+
+impl VerdictKind {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "correct" => Some(Self::Correct),
+            "accepted" => Some(Self::Accepted),
+            "partial" => Some(Self::Partial),
+            "incorrect" => Some(Self::Incorrect),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the word for a [`RecallType`] in reviews.
+fn recall_from_str(s: &str) -> Option<RecallType> {
+    match s {
+        "never" => Some(RecallType::None),
+        "mc" => Some(RecallType::Mc),
+        "text" => Some(RecallType::Text),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn load_history() {
+        let (history, version) = History::load_from_reader(Cursor::new(
+            "EFC3 progress 0.1.0\n\n[question]\ncard: 68e7ede8807ce180\nside: back\nreview: 0 good\nreview: 86400 again 2500\n\n[question]\ncard: ff\nside: mc\nreview: 5 easy\n",
+        ))
+        .unwrap();
+        assert_eq!(version, Some(Version::new(0, 1, 0)));
+        let key = QuestionKey {
+            card: CardId::from_u64(0x68e7_ede8_807c_e180),
+            side: Some(Side::Back),
+        };
+        assert_eq!(
+            history.reviews(key),
+            [
+                Review {
+                    at: Timestamp::from_unix_secs(0),
+                    grade: Grade::Good,
+                    response_time: None,
+                    verdict: None,
+                    recall: None,
+                },
+                Review {
+                    at: Timestamp::from_unix_secs(86400),
+                    grade: Grade::Again,
+                    response_time: Some(Duration::from_millis(2500)),
+                    verdict: None,
+                    recall: None,
+                },
+            ]
+        );
+        let mc = QuestionKey {
+            card: CardId::from_u64(0xff),
+            side: None,
+        };
+        assert_eq!(history.reviews(mc).len(), 1);
+    }
+
+    #[test]
+    fn load_history_verdicts() {
+        let (history, _) = History::load_from_reader(Cursor::new(
+            "[question]\ncard: ff\nside: back\nreview: 0 hard accepted\nreview: 9 again 2500 partial mc\nreview: 12 good text\n",
+        ))
+        .unwrap();
+        let key = QuestionKey {
+            card: CardId::from_u64(0xff),
+            side: Some(Side::Back),
+        };
+        let reviews = history.reviews(key);
+        assert_eq!(reviews[0].verdict, Some(VerdictKind::Accepted));
+        assert_eq!(reviews[0].response_time, None);
+        assert_eq!(reviews[1].verdict, Some(VerdictKind::Partial));
+        assert_eq!(reviews[1].response_time, Some(Duration::from_millis(2500)));
+        assert_eq!(reviews[0].recall, None);
+        assert_eq!(reviews[1].recall, Some(RecallType::Mc));
+        assert_eq!(reviews[2].verdict, None);
+        assert_eq!(reviews[2].recall, Some(RecallType::Text));
+    }
+
+    #[test]
+    fn load_history_invalid() {
+        let load = |s: &str| History::load_from_reader(Cursor::new(s));
+        assert!(load("[question]\ncard: xyz\n").is_err());
+        assert!(load("[question]\ncard: ff\nside: left\n").is_err());
+        assert!(load("[question]\ncard: ff\nside: mc\nreview: 5 great\n").is_err());
+        assert!(load("[question]\ncard: ff\nside: mc\nreview: 5 good 1 2\n").is_err());
+        assert!(load("[question]\ncard: ff\nside: mc\nreview: 5 good right\n").is_err());
+        assert!(load("[question]\ncard: ff\nside: mc\nreview: 5 good 1x\n").is_err());
+        assert!(load("[question]\ncard: ff\nside: mc\nreview: 5 good text correct\n").is_err());
+        // Blocks without a side are skipped.
+        assert!(load("[question]\ncard: ff\nreview: 5 good\n")
+            .unwrap()
+            .0
+            .is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    card::{RecallType, Side},
+    question::VerdictKind,
+    schedule::Grade,
+};
+
+use super::History;
+
+/// Version written on the first line of progress files.
+const VERSION: &str = "0.2.0";
+
+impl History {
+    /// Saves history to a file.
+    pub fn save(&self, file: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file)?);
+        self.save_to_writer(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Writes this history into the given writer, returning the number of
+    /// bytes written.
+    pub fn save_to_writer<W: Write>(&self, mut writer: W) -> io::Result<usize> {
+        let mut buf = std::string::String::new();
+        writeln!(buf, "EFC3 progress {VERSION}").expect("Writing to string can't fail");
+        if let Some(scheduler) = self.scheduler() {
+            writeln!(buf, "scheduler: {scheduler}").expect("Writing to string can't fail");
+        }
+        for (key, reviews) in self.iter() {
+            let side = match key.side {
+                Some(Side::Front) => "front",
+                Some(Side::Back) => "back",
+                None => "mc",
+            };
+            write!(
+                buf,
+                "\n[question]\ncard: {:016x}\nside: {side}\n",
+                key.card.as_u64()
+            )
+            .expect("Writing to string can't fail");
+            if let Some(state) = self.state(key) {
+                writeln!(buf, "state: {state}").expect("Writing to string can't fail");
+            }
+            for review in reviews {
+                write!(
+                    buf,
+                    "review: {} {}",
+                    review.at.unix_secs(),
+                    review.grade.as_str()
+                )
+                .expect("Writing to string can't fail");
+                if let Some(response_time) = review.response_time {
+                    write!(buf, " {}", response_time.as_millis())
+                        .expect("Writing to string can't fail");
+                }
+                if let Some(verdict) = review.verdict {
+                    write!(buf, " {}", verdict.as_str()).expect("Writing to string can't fail");
+                }
+                if let Some(recall) = review.recall {
+                    write!(buf, " {}", recall_as_str(recall))
+                        .expect("Writing to string can't fail");
+                }
+                buf.push('\n');
+            }
+        }
+        writer.write_all(buf.as_bytes())?;
+        Ok(buf.len())
+    }
+}
+
+impl Grade {
+    fn as_str(self) -> &'static str {
+        match self {
+            Grade::Again => "again",
+            Grade::Hard => "hard",
+            Grade::Good => "good",
+            Grade::Easy => "easy",
+        }
+    }
+}
+
+impl VerdictKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            VerdictKind::Correct => "correct",
+            VerdictKind::Accepted => "accepted",
+            VerdictKind::Partial => "partial",
+            VerdictKind::Incorrect => "incorrect",
+        }
+    }
+}
+
+/// The word for `recall` in reviews.  Not the same as in set files, since
+/// reviews are split on whitespace.
+fn recall_as_str(recall: RecallType) -> &'static str {
+    match recall {
+        RecallType::None => "never",
+        RecallType::Mc => "mc",
+        RecallType::Text => "text",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, time::Duration};
+
+    use crate::{
+        card::CardId,
+        question::QuestionKey,
+        schedule::{Review, Timestamp},
+    };
+
+    use super::*;
+
+    #[test]
+    fn save_history() {
+        let history = [
+            (
+                QuestionKey {
+                    card: CardId::from_u64(0xff),
+                    side: None,
+                },
+                Review {
+                    at: Timestamp::from_unix_secs(5),
+                    grade: Grade::Easy,
+                    response_time: Some(Duration::from_millis(1200)),
+                    verdict: None,
+                    recall: None,
+                },
+            ),
+            (
+                QuestionKey {
+                    card: CardId::from_u64(0x68e7_ede8_807c_e180),
+                    side: Some(Side::Back),
+                },
+                Review {
+                    at: Timestamp::from_unix_secs(0),
+                    grade: Grade::Good,
+                    response_time: None,
+                    verdict: Some(VerdictKind::Correct),
+                    recall: Some(RecallType::Mc),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect::<History>();
+
+        let mut buf = Vec::new();
+        let len = history.save_to_writer(&mut buf).unwrap();
+        assert_eq!(len, buf.len());
+        assert_eq!(
+            std::str::from_utf8(&buf).unwrap(),
+            "EFC3 progress 0.2.0\n\n[question]\ncard: 00000000000000ff\nside: mc\nreview: 5 easy 1200\n\n[question]\ncard: 68e7ede8807ce180\nside: back\nreview: 0 good correct mc\n"
+        );
+        assert_eq!(
+            History::load_from_reader(Cursor::new(buf)).unwrap().0,
+            history
+        );
+    }
+
+    #[test]
+    fn save_states() {
+        let key = QuestionKey {
+            card: CardId::from_u64(0xff),
+            side: None,
+        };
+        let review = Review {
+            at: Timestamp::from_unix_secs(5),
+            grade: Grade::Good,
+            response_time: None,
+            verdict: None,
+            recall: None,
+        };
+        let mut history = [(key, review)].into_iter().collect::<History>();
+        history.set_scheduler("sm2");
+        history.set_state(key, "1 1 2.5 86405".into());
+
+        let mut buf = Vec::new();
+        history.save_to_writer(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf).unwrap(),
+            "EFC3 progress 0.2.0\nscheduler: sm2\n\n[question]\ncard: 00000000000000ff\nside: mc\nstate: 1 1 2.5 86405\nreview: 5 good\n"
+        );
+        let (loaded, _) = History::load_from_reader(Cursor::new(buf)).unwrap();
+        assert_eq!(loaded.scheduler(), Some("sm2"));
+        assert_eq!(loaded.state(key), Some("1 1 2.5 86405"));
+        assert_eq!(loaded, history);
+    }
+}
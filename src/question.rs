@@ -1,15 +1,23 @@
-use std::{borrow::Borrow, iter::FusedIterator, ops::Deref, ptr, slice};
+use std::{borrow::Borrow, iter::FusedIterator, ptr, slice, vec};
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use smallvec::SmallVec;
 
-use crate::card::{Flashcard, McCard, RecallType, Set, Side};
+use crate::card::{
+    CardId, CardSide, DecoyMode, Flashcard, Leniency, ListCredit, McCard, RecallSettings,
+    RecallType, Set, Side,
+};
+
+mod decoys;
+mod feedback;
+mod weak;
+
+pub use feedback::{AnswerDiff, DiffKind, DiffSpan};
 
 /// Estimate of average max length of list returned by `Question::mc_answers`;
 /// used to set size of smallvec.
 const MC_LIST_LEN: usize = 6;
-/// How many times to try to find enough decoys before giving up.
-const FIND_DECOY_ATTEMPTS: usize = 24;
 
 /// A question and answer.
 ///
@@ -18,7 +26,7 @@ const FIND_DECOY_ATTEMPTS: usize = 24;
 /// Not that this is NOT a card; some cards may generate as many as 2 qestions
 /// while others may not generate any depending on settings used when converting
 /// cards to questions.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Question<'a> {
     pub(crate) set: &'a Set,
     pub(crate) ty: QuestionTy<'a>,
@@ -30,7 +38,7 @@ impl<'a> PartialEq for Question<'a> {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum QuestionTy<'a> {
     Flashcard {
         card: &'a Flashcard,
@@ -56,25 +64,131 @@ impl<'a> Question<'a> {
         }
     }
 
+    /// How the player should prove they know the answer to this question,
+    /// according to its set.
+    pub fn recall_type(&self) -> &'a RecallType {
+        &self.answer_side().1.typ
+    }
+
+    /// Every text accepted as the answer to this question, not counting
+    /// variations allowed by its set's rules.
+    pub fn answers(&self) -> impl Iterator<Item = &'a str> {
+        self.answer_side().0.iter_text()
+    }
+
+    /// Identifies what this question asks about, for keeping track of how
+    /// well it is remembered.
+    pub fn key(&self) -> QuestionKey {
+        match self.ty {
+            QuestionTy::Flashcard { card, side } => QuestionKey {
+                card: card.id(),
+                side: Some(side),
+            },
+            QuestionTy::McCard { card } => QuestionKey {
+                card: card.id(),
+                side: None,
+            },
+        }
+    }
+
     /// Whether or not a string is a correct answer to this question.
     ///
     /// Some questions may have more than one correct answer.
     pub fn is_correct_answer(&self, answer: &str) -> bool {
+        self.check_answer(answer).is_correct()
+    }
+
+    /// Returns how correct `answer` is, including which answer it matched and
+    /// which rules had to be relaxed to accept it.
+    ///
+    /// Lets frontends point out small mistakes such as missing accents in
+    /// answers that were still accepted.
+    ///
+    /// Answers with typos are never accepted if they are the answer to a
+    /// different card or one of this card's decoys.  Answers that give only
+    /// some of the items of a list answer are [`AnswerVerdict::Partial`].
+    pub fn check_answer(&self, answer: &str) -> AnswerVerdict<'a> {
+        let (answer_side, rules) = self.answer_side();
+        let Some((variant, leniency)) = answer_side.match_text(rules, answer) else {
+            return match answer_side.list_credit(rules, answer) {
+                Some((variant, credit)) => AnswerVerdict::Partial {
+                    variant,
+                    expected: answer_side
+                        .get_text(variant)
+                        .expect("Matched list should exist"),
+                    credit,
+                },
+                None => AnswerVerdict::Incorrect,
+            };
+        };
+        if leniency.typos > 0 && self.is_other_answer(answer) {
+            return AnswerVerdict::Incorrect;
+        }
+
+        let expected = answer_side
+            .get_text(variant)
+            .expect("Matched text should exist");
+        if leniency.is_exact() {
+            AnswerVerdict::Correct { variant, expected }
+        } else {
+            AnswerVerdict::Accepted {
+                variant,
+                expected,
+                leniency,
+            }
+        }
+    }
+
+    /// The side of the card with the answer to this question and the rules for
+    /// checking it.
+    fn answer_side(&self) -> (&'a CardSide, &'a RecallSettings) {
+        match self.ty {
+            QuestionTy::Flashcard { card, side } => {
+                (&card[side], self.set.flashcard_recall_settings(side))
+            }
+            QuestionTy::McCard { card } => (&card.answer, &self.set.recall_mc),
+        }
+    }
+
+    /// Whether `answer` is, without any typos, the answer to a different card
+    /// or one of this card's decoys.
+    fn is_other_answer(&self, answer: &str) -> bool {
         match self.ty {
             QuestionTy::Flashcard { card, side } => {
-                card[side].matches_text(self.set.flashcard_recall_settings(side), answer)
+                let rules = self.set.flashcard_recall_settings(side).without_typos();
+                self.set
+                    .flashcards
+                    .iter()
+                    .filter(|other| !ptr::eq(card, *other))
+                    .any(|other| other[side].matches_text(&rules, answer))
+            }
+            QuestionTy::McCard { card } => {
+                let rules = self.set.recall_mc.without_typos();
+                card.decoys.matches_text(&rules, answer)
+                    || self
+                        .set
+                        .mc_cards
+                        .iter()
+                        .filter(|other| !ptr::eq(card, *other))
+                        .any(|other| other.answer.matches_text(&rules, answer))
             }
-            QuestionTy::McCard { card } => card.answer.matches_text(&self.set.recall_mc, answer),
         }
     }
 
     /// Returns a shuffled list containing the correct answer to this question
-    /// and `count - 1` (or the max number of possible decoys if that is
+    /// and `count - 1` (or the number of distinct possible decoys if that is
     /// smaller) decoys.
     ///
     /// For [`Flashcard`]s decoys come from the other flashcards.
     ///
-    /// For [`McCard`]s decoys come from provided decoys.
+    /// For [`McCard`]s decoys come from provided decoys, and from the answers
+    /// to other multiple choice cards if there aren't enough and
+    /// [`RecallSettings::borrow_decoys`] is set.
+    ///
+    /// Decoys are picked as set by [`RecallSettings::decoys`].  Questions about
+    /// [`McCard`]s with at least two decoys may also get a [`SpecialChoice`]
+    /// as set by [`RecallSettings::none_of_the_above`] and
+    /// [`RecallSettings::all_of_the_above`], counted in `count`.
     pub fn mc_answers<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Option<McList<'a>> {
         // Remember to make sure this only returns one correct answer.
         match self.ty {
@@ -85,31 +199,44 @@ impl<'a> Question<'a> {
 
                 let flashcard_count = self.set.flashcards.len();
                 let count = count.min(flashcard_count);
+                // Decoys only have to differ from the answer, typos or not.
+                let rules = self.set.flashcard_recall_settings(side).without_typos();
 
                 let mut list = SmallVec::<[_; MC_LIST_LEN]>::with_capacity(count);
-                for _ in 0..FIND_DECOY_ATTEMPTS {
-                    let random_card = self
-                        .set
-                        .flashcards
-                        .choose(rng)
-                        .expect("Can't have card from list if list is empty");
-                    // Get out early if accidently pick card question is about.
-                    if ptr::eq(card, random_card) {
-                        continue;
-                    }
-
-                    let Some(text) = random_card[side].any_text(rng) else {
-                        continue;
-                    };
-                    if answer_side.matches_text(self.set.flashcard_recall_settings(side), text)
-                        || list.contains(&text)
-                    {
-                        continue;
+                match rules.decoys {
+                    DecoyMode::Random => {
+                        let others = self
+                            .set
+                            .flashcards
+                            .iter()
+                            .filter(|other| !ptr::eq(card, *other))
+                            .map(|other| &other[side]);
+                        list.extend(decoys::random_decoys(
+                            others,
+                            count.saturating_sub(1),
+                            |text| !answer_side.matches_text(&rules, text),
+                            rng,
+                        ));
                     }
-
-                    list.push(text);
-                    if list.len() == count - 1 {
-                        break;
+                    DecoyMode::Similar => {
+                        let candidates = self
+                            .set
+                            .flashcards
+                            .iter()
+                            .filter(|other| !ptr::eq(card, *other))
+                            .flat_map(|other| {
+                                other[side]
+                                    .iter_text()
+                                    .map(|text| (text, other.tags.as_slice()))
+                            })
+                            .filter(|(text, _)| !answer_side.matches_text(&rules, text));
+                        list.extend(decoys::most_similar(
+                            correct_text,
+                            &card.tags,
+                            candidates,
+                            count.saturating_sub(1),
+                            rng,
+                        ));
                     }
                 }
 
@@ -122,29 +249,101 @@ impl<'a> Question<'a> {
                 Some(McList {
                     list,
                     correct_index,
+                    special: None,
                 })
             }
             QuestionTy::McCard { card } => {
                 let correct_answer = card.answer.any_text(rng)?;
-                let decoys = &card.decoys;
-
-                let count = count.min(decoys.text_count() + 1);
+                let rules = &self.set.recall_mc;
+                let wanted = count.saturating_sub(1);
+
+                let mut decoys = match rules.decoys {
+                    DecoyMode::Random => card.decoys.choose_text(rng, wanted).collect::<Vec<_>>(),
+                    DecoyMode::Similar => decoys::most_similar(
+                        correct_answer,
+                        &[],
+                        card.decoys.iter_text().map(|text| (text, &[][..])),
+                        wanted,
+                        rng,
+                    ),
+                };
+                if rules.borrow_decoys && decoys.len() < wanted {
+                    let others = self
+                        .set
+                        .mc_cards
+                        .iter()
+                        .filter(|other| !ptr::eq(card, *other));
+                    let rules = rules.without_typos();
+                    let is_decoy = |text: &str| {
+                        !card.decoys.matches_text(&rules, text)
+                            && !card.answer.matches_text(&rules, text)
+                    };
+                    let needed = wanted - decoys.len();
+                    let borrowed = match rules.decoys {
+                        DecoyMode::Random => decoys::random_decoys(
+                            others.map(|other| &other.answer),
+                            needed,
+                            is_decoy,
+                            rng,
+                        ),
+                        DecoyMode::Similar => decoys::most_similar(
+                            correct_answer,
+                            &[],
+                            others
+                                .flat_map(|other| other.answer.iter_text())
+                                .filter(|text| is_decoy(text))
+                                .map(|text| (text, &[][..])),
+                            needed,
+                            rng,
+                        ),
+                    };
+                    decoys.extend(borrowed);
+                }
                 // If there are no decoys this card is probably a mistake.
-                if count < 2 {
+                if decoys.is_empty() {
                     return None;
                 }
 
-                let mut decoys = decoys.choose_text(rng, count - 1);
-                let correct_index = rng.gen_range(0..count);
+                let special = special_choice(rules, card, count, decoys.len(), rng);
+                if let Some((SpecialChoice::AllOfTheAbove, true)) = special {
+                    let mut list = card.answer.iter_text().collect::<SmallVec<_>>();
+                    list.shuffle(rng);
+                    list.truncate(count - 1);
+                    return Some(McList {
+                        correct_index: list.len(),
+                        list,
+                        special: Some(SpecialChoice::AllOfTheAbove),
+                    });
+                }
+                // The answer is left out when "None of the above" is correct.
+                let withhold = special == Some((SpecialChoice::NoneOfTheAbove, true));
+                // Own decoys come first, so they are kept over borrowed ones.
+                decoys.truncate(match special {
+                    Some(_) if !withhold => count - 2,
+                    _ => count - 1,
+                });
+                if rules.decoys == DecoyMode::Similar || rules.borrow_decoys {
+                    decoys.shuffle(rng);
+                }
+                let special = special.map(|(choice, _)| choice);
+                if withhold {
+                    return Some(McList {
+                        correct_index: decoys.len(),
+                        list: decoys.into_iter().collect(),
+                        special,
+                    });
+                }
+                let correct_index = rng.gen_range(0..=decoys.len());
 
-                let mut list = SmallVec::with_capacity(count);
-                list.extend(decoys.by_ref().take(correct_index));
+                let mut list = SmallVec::with_capacity(decoys.len() + 1);
+                list.extend(decoys.drain(..correct_index));
                 list.push(correct_answer);
                 list.extend(decoys);
 
                 Some(McList {
                     list,
                     correct_index,
+                    special,
                 })
             }
         }
@@ -165,30 +364,237 @@ impl<'a> Question<'a> {
     }
 }
 
-/// A list of decoys and one correct answer to a multiple choice question.
+/// Picks whether a multiple choice question about `card` with up to `count`
+/// choices and `decoys` decoys available gets a special choice as set by
+/// `rules`, and whether it is the correct one.
+///
+/// Special choices are correct as often as any other choice.
+fn special_choice<R: Rng + ?Sized>(
+    rules: &RecallSettings,
+    card: &McCard,
+    count: usize,
+    decoys: usize,
+    rng: &mut R,
+) -> Option<(SpecialChoice, bool)> {
+    // Special choices need at least two other choices to make sense, even
+    // when the answer is left out.
+    if count < 3 || decoys < 2 {
+        return None;
+    }
+    let count = count.min(decoys + 2);
+    let mut roll = |percent: u32| percent > 0 && rng.gen_ratio(percent.min(100), 100);
+    let choice = if card.answer.iter_text().nth(1).is_some() && roll(rules.all_of_the_above) {
+        SpecialChoice::AllOfTheAbove
+    } else if roll(rules.none_of_the_above) {
+        SpecialChoice::NoneOfTheAbove
+    } else {
+        return None;
+    };
+    Some((choice, rng.gen_range(0..count) == 0))
+}
+
+/// Identifies what a [`Question`] asks about.
+///
+/// Created by [`Question::key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QuestionKey {
+    /// The card the question is from.
+    pub card: CardId,
+    /// The side of the flashcard to recall, or `None` for multiple choice
+    /// cards.
+    pub side: Option<Side>,
+}
+
+/// How correct an answer to a [`Question`] is.
+///
+/// Created by [`Question::check_answer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AnswerVerdict<'a> {
+    /// The answer matched exactly.
+    Correct {
+        /// Index of the text on the card that was matched.
+        variant: usize,
+        /// The text on the card that was matched.
+        expected: &'a str,
+    },
+    /// The answer was accepted, but only because some rules were relaxed.
+    Accepted {
+        /// Index of the text on the card that was matched.
+        variant: usize,
+        /// The text on the card that was matched.
+        expected: &'a str,
+        /// The rules that had to be relaxed.
+        leniency: Leniency,
+    },
+    /// The answer gave some, but not enough, of the items of a list, or gave
+    /// items that aren't in it.
+    Partial {
+        /// Index of the list on the card.
+        variant: usize,
+        /// The text of the list on the card.
+        expected: &'a str,
+        /// How much of the list was given.
+        credit: ListCredit<'a>,
+    },
+    /// The answer was wrong.
+    Incorrect,
+}
+
+impl<'a> AnswerVerdict<'a> {
+    /// Whether the answer should be counted as correct.
+    pub fn is_correct(&self) -> bool {
+        matches!(
+            self,
+            AnswerVerdict::Correct { .. } | AnswerVerdict::Accepted { .. }
+        )
+    }
+
+    /// The text on the card the answer matched, if it was correct.
+    pub fn expected(&self) -> Option<&'a str> {
+        match self {
+            AnswerVerdict::Correct { expected, .. } | AnswerVerdict::Accepted { expected, .. } => {
+                Some(expected)
+            }
+            AnswerVerdict::Partial { .. } | AnswerVerdict::Incorrect => None,
+        }
+    }
+
+    /// How much of a list answer was given, if only some of it was.
+    pub fn credit(&self) -> Option<&ListCredit<'a>> {
+        match self {
+            AnswerVerdict::Partial { credit, .. } => Some(credit),
+            _ => None,
+        }
+    }
+
+    /// The rules that had to be relaxed to accept the answer, if it was
+    /// correct.
+    pub fn leniency(&self) -> Option<Leniency> {
+        match self {
+            AnswerVerdict::Correct { .. } => Some(Leniency::default()),
+            AnswerVerdict::Accepted { leniency, .. } => Some(*leniency),
+            AnswerVerdict::Partial { .. } | AnswerVerdict::Incorrect => None,
+        }
+    }
+
+    /// Which kind of verdict this is, without the details.
+    pub fn kind(&self) -> VerdictKind {
+        match self {
+            AnswerVerdict::Correct { .. } => VerdictKind::Correct,
+            AnswerVerdict::Accepted { .. } => VerdictKind::Accepted,
+            AnswerVerdict::Partial { .. } => VerdictKind::Partial,
+            AnswerVerdict::Incorrect => VerdictKind::Incorrect,
+        }
+    }
+}
+
+/// The kind of an [`AnswerVerdict`], which can be stored with a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerdictKind {
+    /// See [`AnswerVerdict::Correct`].
+    Correct,
+    /// See [`AnswerVerdict::Accepted`].
+    Accepted,
+    /// See [`AnswerVerdict::Partial`].
+    Partial,
+    /// See [`AnswerVerdict::Incorrect`].
+    Incorrect,
+}
+
+/// A list of decoys and one correct answer to a multiple choice question,
+/// optionally followed by a [`SpecialChoice`].
+///
+/// Use [`McList::choices`] to show every choice and [`McList::select`] to
+/// check the one picked.
 #[derive(Debug, Clone)]
 pub struct McList<'a> {
     list: SmallVec<[&'a str; MC_LIST_LEN]>,
     correct_index: usize,
+    special: Option<SpecialChoice>,
+}
+
+/// A choice that isn't text from a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialChoice {
+    /// Correct when the answer to the question isn't one of the other
+    /// choices.
+    NoneOfTheAbove,
+    /// Correct when every other choice is an answer to the question.
+    AllOfTheAbove,
+}
+
+impl SpecialChoice {
+    /// Text to show for this choice.
+    pub fn text(self) -> &'static str {
+        match self {
+            SpecialChoice::NoneOfTheAbove => "None of the above",
+            SpecialChoice::AllOfTheAbove => "All of the above",
+        }
+    }
 }
 
-impl<'a> Deref for McList<'a> {
-    type Target = [&'a str];
+/// One of the choices in an [`McList`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice<'a> {
+    Text(&'a str),
+    Special(SpecialChoice),
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.list
+impl<'a> Choice<'a> {
+    /// Text to show for this choice.
+    pub fn text(self) -> &'a str {
+        match self {
+            Choice::Text(text) => text,
+            Choice::Special(special) => special.text(),
+        }
     }
 }
 
 impl<'a> McList<'a> {
-    /// The index into this where the correct answer is stored.
+    /// The index into [`McList::choices`] of the correct choice.
     pub fn correct_index(&self) -> usize {
         self.correct_index
     }
 
-    /// The element of this list that is the correct answer.
-    pub fn correct(&self) -> &'a str {
-        self[self.correct_index()]
+    /// The correct choice.
+    pub fn correct(&self) -> Choice<'a> {
+        self.choice(self.correct_index)
+            .expect("Correct index should be in range")
+    }
+
+    /// The special choice shown after the text choices, if there is one.
+    pub fn special(&self) -> Option<SpecialChoice> {
+        self.special
+    }
+
+    /// Every choice in the order they should be shown: the text choices, then
+    /// the special choice if there is one.
+    pub fn choices(&self) -> impl ExactSizeIterator<Item = Choice<'a>> + '_ {
+        (0..self.choice_count()).map(|index| {
+            self.choice(index)
+                .expect("Index should be less than choice count")
+        })
+    }
+
+    /// Number of choices, including the special choice.
+    pub fn choice_count(&self) -> usize {
+        self.list.len() + usize::from(self.special.is_some())
+    }
+
+    /// The choice at `index` in [`McList::choices`].
+    pub fn choice(&self, index: usize) -> Option<Choice<'a>> {
+        match self.list.get(index) {
+            Some(text) => Some(Choice::Text(text)),
+            None if index == self.list.len() => self.special.map(Choice::Special),
+            None => None,
+        }
+    }
+
+    /// Whether picking the choice at `index` in [`McList::choices`] is
+    /// correct, or `None` if there is no choice at `index`.
+    pub fn select(&self, index: usize) -> Option<bool> {
+        (index < self.choice_count()).then_some(index == self.correct_index)
     }
 }
 
@@ -201,6 +607,8 @@ pub struct Conditions {
     pub include_card_front: bool,
     /// Whether to include multiple choice cards.
     pub include_mc: bool,
+    /// What order to return questions in.
+    pub order: QuestionOrder,
 }
 
 impl Conditions {
@@ -208,12 +616,14 @@ impl Conditions {
         include_card_back: true,
         include_card_front: true,
         include_mc: true,
+        order: QuestionOrder::Grouped,
     };
 
     pub const INCLUDE_NONE: Self = Self {
         include_card_back: false,
         include_card_front: false,
         include_mc: false,
+        order: QuestionOrder::Grouped,
     };
 }
 
@@ -223,10 +633,35 @@ impl Default for Conditions {
             include_card_front: true,
             include_card_back: true,
             include_mc: true,
+            order: QuestionOrder::Grouped,
         }
     }
 }
 
+/// Order of the questions returned by [`Set::questions`].
+///
+/// Seeded orders use a portable random number generator, so they are the same
+/// on any platform for the same set and seed.  They may change in new minor
+/// versions of this library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuestionOrder {
+    /// Every question asking for the back of a flashcard, then every question
+    /// asking for the front, then every multiple choice question, each in the
+    /// order the cards are in the set.
+    #[default]
+    Grouped,
+    /// A random order.
+    Shuffled { seed: u64 },
+    /// A question asking for the back, then one asking for the front, then
+    /// a multiple choice question, and so on, with the cards of each kind in
+    /// the order they are in the set.
+    RoundRobin,
+    /// Like [`QuestionOrder::RoundRobin`] but with the cards of each kind in
+    /// a random order, and the questions asking for the front and the back
+    /// of the same flashcard about half of the questions apart.
+    Interleaved { seed: u64 },
+}
+
 impl Set {
     /// Returns an iterator over all the questions that could be asked to prove
     /// knowledge of this set.  Allows for setting conditions to filter out
@@ -236,7 +671,7 @@ impl Set {
     }
 
     fn questions_inner(&self, conditions: &Conditions) -> Questions<'_> {
-        Questions {
+        let questions = Questions {
             set: self,
             flashcards_front: if conditions.include_card_front
                 && self.recall_front.typ != RecallType::None
@@ -257,28 +692,109 @@ impl Set {
             } else {
                 [].iter()
             },
-        }
+            ordered: Vec::new().into_iter(),
+        };
+
+        let kinds = || {
+            [
+                questions
+                    .flashcards_back
+                    .clone()
+                    .map(|card| Question::from_flashcard(card, Side::Back, self))
+                    .collect::<Vec<_>>(),
+                questions
+                    .flashcards_front
+                    .clone()
+                    .map(|card| Question::from_flashcard(card, Side::Front, self))
+                    .collect(),
+                questions
+                    .mc_cards
+                    .clone()
+                    .map(|card| Question::from_mc_card(card, self))
+                    .collect(),
+            ]
+        };
+        let ordered = match conditions.order {
+            QuestionOrder::Grouped => return questions,
+            QuestionOrder::Shuffled { seed } => {
+                let mut ordered = kinds().into_iter().flatten().collect::<Vec<_>>();
+                ordered.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+                ordered
+            }
+            QuestionOrder::RoundRobin => round_robin(kinds()),
+            QuestionOrder::Interleaved { seed } => {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let mut kinds = kinds();
+                // Shuffle flashcards once for both sides so the front of each
+                // card is half a rotation behind its back.
+                let mut flashcards = (0..self.flashcards.len()).collect::<Vec<_>>();
+                flashcards.shuffle(&mut rng);
+                let [back, front, _] = &mut kinds;
+                if !back.is_empty() {
+                    *back = flashcards.iter().map(|&i| back[i].clone()).collect();
+                }
+                if !front.is_empty() {
+                    *front = flashcards.iter().map(|&i| front[i].clone()).collect();
+                    let half = front.len() / 2;
+                    front.rotate_left(half);
+                }
+                kinds[2].shuffle(&mut rng);
+                round_robin(kinds)
+            }
+        };
+        Questions::from_ordered(self, ordered)
     }
 }
 
+/// Takes one question of each kind in turn until all are taken.
+fn round_robin<'a, const N: usize>(kinds: [Vec<Question<'a>>; N]) -> Vec<Question<'a>> {
+    let len = kinds.iter().map(Vec::len).sum();
+    let mut kinds = kinds.map(Vec::into_iter);
+    let mut ordered = Vec::with_capacity(len);
+    while ordered.len() < len {
+        ordered.extend(kinds.iter_mut().filter_map(Iterator::next));
+    }
+    ordered
+}
+
 #[derive(Debug, Clone)]
 /// An iterator over the [`Question`]s extracted from a [`Set`].
 ///
-/// The order questions are returned in should not be depended on.
+/// Questions are returned in the order set by [`Conditions::order`].
 pub struct Questions<'a> {
     set: &'a Set,
     flashcards_back: slice::Iter<'a, Flashcard>,
     flashcards_front: slice::Iter<'a, Flashcard>,
     mc_cards: slice::Iter<'a, McCard>,
+    /// Questions in any order other than [`QuestionOrder::Grouped`], returned
+    /// before the cards above.
+    ordered: vec::IntoIter<Question<'a>>,
+}
+
+impl<'a> Questions<'a> {
+    /// Returns `ordered`, which must be questions from `set`, in order.
+    pub(crate) fn from_ordered(set: &'a Set, ordered: Vec<Question<'a>>) -> Self {
+        Self {
+            set,
+            flashcards_back: [].iter(),
+            flashcards_front: [].iter(),
+            mc_cards: [].iter(),
+            ordered: ordered.into_iter(),
+        }
+    }
 }
 
 impl<'a> Iterator for Questions<'a> {
     type Item = Question<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.flashcards_back
+        self.ordered
             .next()
-            .map(|card| Question::from_flashcard(card, Side::Back, self.set))
+            .or_else(|| {
+                self.flashcards_back
+                    .next()
+                    .map(|card| Question::from_flashcard(card, Side::Back, self.set))
+            })
             .or_else(|| {
                 self.flashcards_front
                     .next()
@@ -308,6 +824,7 @@ impl<'a> Iterator for Questions<'a> {
         Self: Sized,
         F: FnMut(Self::Item),
     {
+        self.ordered.for_each(&mut f);
         self.flashcards_back
             .map(|card| Question::from_flashcard(card, Side::Back, self.set))
             .for_each(&mut f);
@@ -324,10 +841,11 @@ impl<'a> Iterator for Questions<'a> {
         Self: Sized,
         F: FnMut(B, Self::Item) -> B,
     {
+        let acc = self.ordered.fold(init, &mut f);
         let acc = self
             .flashcards_back
             .map(|card| Question::from_flashcard(card, Side::Back, self.set))
-            .fold(init, &mut f);
+            .fold(acc, &mut f);
         let acc = self
             .flashcards_front
             .map(|card| Question::from_flashcard(card, Side::Front, self.set))
@@ -340,7 +858,10 @@ impl<'a> Iterator for Questions<'a> {
 
 impl<'a> ExactSizeIterator for Questions<'a> {
     fn len(&self) -> usize {
-        self.flashcards_back.len() + self.flashcards_front.len() + self.mc_cards.len()
+        self.ordered.len()
+            + self.flashcards_back.len()
+            + self.flashcards_front.len()
+            + self.mc_cards.len()
     }
 }
 
@@ -352,8 +873,21 @@ mod tests {
 
     use rand::SeedableRng;
 
+    use crate::card::{AnswerPattern, Decoys, ListAnswer, OptionalAnswer, Typos};
+
     use super::*;
 
+    /// The text choices in `answers`, leaving out any special choice.
+    fn texts<'a>(answers: &McList<'a>) -> Vec<&'a str> {
+        answers
+            .choices()
+            .filter_map(|choice| match choice {
+                Choice::Text(text) => Some(text),
+                Choice::Special(_) => None,
+            })
+            .collect()
+    }
+
     const POSSIBLE_CONDITIONS: &[Conditions; 8] = &[
         Conditions::INCLUDE_NONE,
         Conditions {
@@ -517,6 +1051,240 @@ mod tests {
         assert!(question.is_correct_answer("A "));
     }
 
+    #[test]
+    fn check_answer_missing_accents() {
+        let mut set = Set::example(
+            RecallSettings::default(),
+            RecallSettings {
+                check_accents: false,
+                ..Default::default()
+            },
+            RecallSettings::default(),
+        );
+        set.flashcards[0] = Flashcard::new("boy", "niño");
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        assert_eq!(
+            question.check_answer("niño"),
+            AnswerVerdict::Correct {
+                variant: 0,
+                expected: "niño"
+            }
+        );
+        assert_eq!(
+            question.check_answer("nino"),
+            AnswerVerdict::Accepted {
+                variant: 0,
+                expected: "niño",
+                leniency: Leniency {
+                    accents: true,
+                    ..Default::default()
+                }
+            }
+        );
+        assert_eq!(question.check_answer("nina"), AnswerVerdict::Incorrect);
+    }
+
+    #[test]
+    fn check_answer_variants() {
+        let mut set = Set::example_recall_default();
+        set.flashcards[0].back = CardSide::new_multi(["colour", "color"]);
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        assert_eq!(
+            question.check_answer("color"),
+            AnswerVerdict::Correct {
+                variant: 1,
+                expected: "color"
+            }
+        );
+        let verdict = question.check_answer("Colour");
+        assert_eq!(verdict.expected(), Some("colour"));
+        assert_eq!(
+            verdict,
+            AnswerVerdict::Accepted {
+                variant: 0,
+                expected: "colour",
+                leniency: Leniency {
+                    caps: true,
+                    ..Default::default()
+                }
+            }
+        );
+        assert!(verdict.is_correct());
+        assert!(!question.check_answer("colors").is_correct());
+    }
+
+    #[test]
+    fn optional_part_answers() {
+        let mut set = Set::example_recall_default();
+        let mut back = CardSide::empty();
+        back.push_optional(OptionalAnswer::new("(to) run"));
+        set.flashcards[0] = Flashcard {
+            front: CardSide::new("correr"),
+            back,
+            tags: Vec::new(),
+            id_text: None,
+        };
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        for answer in ["run", "to run"] {
+            assert_eq!(
+                question.check_answer(answer),
+                AnswerVerdict::Correct {
+                    variant: 0,
+                    expected: "(to) run"
+                }
+            );
+        }
+        assert!(!question.is_correct_answer("to"));
+        assert!(!question.is_correct_answer("(to) run"));
+    }
+
+    #[test]
+    fn pattern_answers() {
+        let mut set = Set::example_recall_default();
+        let mut back = CardSide::empty();
+        back.push_pattern(AnswerPattern::new("colou?r").unwrap());
+        set.flashcards[0] = Flashcard {
+            front: CardSide::new("hue"),
+            back,
+            tags: Vec::new(),
+            id_text: None,
+        };
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let mut questions = set.questions(Conditions::INCLUDE_ALL);
+        let hue = questions.next().unwrap();
+        assert_eq!(hue.question(&mut rng), Some("hue"));
+        assert_eq!(
+            hue.check_answer("colour"),
+            AnswerVerdict::Correct {
+                variant: 0,
+                expected: "color"
+            }
+        );
+        assert!(hue.is_correct_answer("Color"));
+        assert!(!hue.is_correct_answer("colr"));
+        assert_eq!(
+            hue.mc_answers(6, &mut rng).unwrap().correct(),
+            Choice::Text("color")
+        );
+
+        // Asked in reverse.
+        let reverse = questions.nth(5).unwrap();
+        assert_eq!(reverse.question(&mut rng), Some("color"));
+        assert!(reverse.is_correct_answer("hue"));
+
+        // Used as a decoy.
+        let b = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .nth(1)
+            .unwrap();
+        let answers = b.mc_answers(6, &mut rng).unwrap();
+        assert!(texts(&answers).contains(&"color"));
+    }
+
+    #[test]
+    fn list_answers() {
+        let mut set = Set::example_recall_default();
+        let mut back = CardSide::empty();
+        back.push_list(ListAnswer::new("red, blue, yellow"));
+        set.flashcards[0] = Flashcard {
+            front: CardSide::new("primary colors"),
+            back,
+            tags: Vec::new(),
+            id_text: None,
+        };
+
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        assert_eq!(
+            question.check_answer("yellow, red, blue"),
+            AnswerVerdict::Correct {
+                variant: 0,
+                expected: "red, blue, yellow"
+            }
+        );
+        assert_eq!(
+            question.check_answer("blue, red"),
+            AnswerVerdict::Partial {
+                variant: 0,
+                expected: "red, blue, yellow",
+                credit: ListCredit {
+                    given: 2,
+                    required: 3,
+                    missing: vec!["yellow"],
+                    wrong: 0,
+                },
+            }
+        );
+        assert!(!question.is_correct_answer("blue, red"));
+        assert_eq!(question.check_answer("green"), AnswerVerdict::Incorrect);
+    }
+
+    #[test]
+    fn check_answer_typos() {
+        let typo_rules = RecallSettings {
+            allowed_typos: Typos::Count(1),
+            ..Default::default()
+        };
+        let mut set = Set::example(RecallSettings::default(), typo_rules.clone(), typo_rules);
+        set.flashcards[0] = Flashcard::new("to get", "receive");
+        set.flashcards[1] = Flashcard::new("hair on a horse", "mane");
+        set.flashcards[2] = Flashcard::new("most important", "main");
+        set.mc_cards[0].answer = CardSide::new("cat");
+        set.mc_cards[0].decoys = ["bat", "dog"].into_iter().collect();
+        set.mc_cards[1].answer = CardSide::new("cut");
+
+        let mut questions = set.questions(Conditions {
+            include_card_back: true,
+            include_mc: true,
+            ..Conditions::INCLUDE_NONE
+        });
+        let receive = questions.next().unwrap();
+        assert_eq!(
+            receive.check_answer("recieve").leniency(),
+            Some(Leniency {
+                typos: 1,
+                ..Default::default()
+            })
+        );
+        let mane = questions.next().unwrap();
+        assert!(mane.is_correct_answer("mane"));
+        assert!(mane.is_correct_answer("man"));
+        assert!(!mane.is_correct_answer("main"));
+
+        let cat = questions.nth(4).unwrap();
+        assert!(cat.is_correct_answer("cat"));
+        assert!(cat.is_correct_answer("car"));
+        assert!(!cat.is_correct_answer("bat"));
+        assert!(!cat.is_correct_answer("cut"));
+    }
+
     #[test]
     fn mc_answers_flashcard() {
         let set = Set::example_recall_default();
@@ -530,13 +1298,13 @@ mod tests {
             .unwrap();
         let mut rng = rand::thread_rng();
         let answers = question.mc_answers(6, &mut rng).unwrap();
-        assert_eq!(answers.len(), 6);
-        assert!(answers.contains(&"1"));
-        assert!(answers.contains(&"2"));
-        assert!(answers.contains(&"3"));
-        assert!(answers.contains(&"4"));
-        assert!(answers.contains(&"5"));
-        assert_eq!(answers.correct(), "0");
+        assert_eq!(answers.choice_count(), 6);
+        assert!(texts(&answers).contains(&"1"));
+        assert!(texts(&answers).contains(&"2"));
+        assert!(texts(&answers).contains(&"3"));
+        assert!(texts(&answers).contains(&"4"));
+        assert!(texts(&answers).contains(&"5"));
+        assert_eq!(answers.correct(), Choice::Text("0"));
     }
 
     #[test]
@@ -551,19 +1319,203 @@ mod tests {
             .unwrap();
         let mut rng = rand::thread_rng();
         let answers = question.mc_answers(4, &mut rng).unwrap();
-        assert_eq!(answers.len(), 4);
-        assert!(answers.contains(&"0decoy0"));
-        assert!(answers.contains(&"0decoy1"));
-        assert!(answers.contains(&"0decoy2"));
-        assert_eq!(answers.correct(), "0answer");
+        assert_eq!(answers.choice_count(), 4);
+        assert!(texts(&answers).contains(&"0decoy0"));
+        assert!(texts(&answers).contains(&"0decoy1"));
+        assert!(texts(&answers).contains(&"0decoy2"));
+        assert_eq!(answers.correct(), Choice::Text("0answer"));
+    }
+
+    #[test]
+    fn mc_answers_similar() {
+        let mut set = Set::example_recall_default();
+        set.recall_back.decoys = DecoyMode::Similar;
+        set.recall_mc.decoys = DecoyMode::Similar;
+        set.mc_cards[0].decoys = ["0decoy0", "far off", "0decoy1"].into_iter().collect();
+        set.flashcards = [
+            ("France", "Paris", "capitals"),
+            ("Italy", "Rome", "capitals"),
+            ("Spain", "Madrid", "capitals"),
+            ("plants", "photosynthesis", "biology"),
+            ("revolution", "1789", "history"),
+        ]
+        .into_iter()
+        .map(|(front, back, tag)| {
+            let mut card = Flashcard::new(front, back);
+            card.tags.push(tag.into());
+            card
+        })
+        .collect();
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        let answers = question.mc_answers(3, &mut rng).unwrap();
+        let mut sorted = texts(&answers);
+        sorted.sort();
+        assert_eq!(sorted, ["Madrid", "Paris", "Rome"]);
+        assert_eq!(answers.correct(), Choice::Text("Paris"));
+
+        let question = set
+            .questions(Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        let answers = question.mc_answers(3, &mut rng).unwrap();
+        assert!(!texts(&answers).contains(&"far off"));
+        assert_eq!(answers.choice_count(), 3);
+    }
+
+    #[test]
+    fn mc_answers_borrow_decoys() {
+        let mut set = Set::example_recall_default();
+        set.mc_cards[0].decoys = Decoys::empty();
+        set.mc_cards[1].answer.push_text("0ANSWER");
+        set.recall_mc.check_caps = false;
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        fn question(set: &Set) -> Question<'_> {
+            set.questions(Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap()
+        }
+        assert!(question(&set).mc_answers(4, &mut rng).is_none());
+
+        set.recall_mc.borrow_decoys = true;
+        for _ in 0..16 {
+            let answers = question(&set).mc_answers(4, &mut rng).unwrap();
+            assert_eq!(answers.choice_count(), 4);
+            assert_eq!(answers.correct(), Choice::Text("0answer"));
+            let mut sorted = texts(&answers);
+            sorted.sort();
+            assert_eq!(sorted, ["0answer", "1answer", "2answer", "3answer"]);
+        }
+
+        // Borrowed answers the same as the card's own decoys are left out.
+        set.mc_cards[0].decoys.push_text("2ANSWER");
+        for _ in 0..16 {
+            let answers = question(&set).mc_answers(4, &mut rng).unwrap();
+            let mut sorted = texts(&answers);
+            sorted.sort();
+            assert_eq!(sorted, ["0answer", "1answer", "2ANSWER", "3answer"]);
+        }
+
+        set.recall_mc.decoys = DecoyMode::Similar;
+        set.mc_cards[0].decoys.push_text("own");
+        let answers = question(&set).mc_answers(3, &mut rng).unwrap();
+        assert_eq!(answers.choice_count(), 3);
+        assert!(texts(&answers).contains(&"own"));
+    }
+
+    #[test]
+    fn mc_answers_none_of_the_above() {
+        let mut set = Set::example_recall_default();
+        set.recall_mc.none_of_the_above = 100;
+        let question = set
+            .questions(Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        let (mut withheld, mut shown) = (0, 0);
+        for seed in 0..64 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let answers = question.mc_answers(4, &mut rng).unwrap();
+            assert_eq!(texts(&answers).len(), 3);
+            assert_eq!(answers.choice_count(), 4);
+            assert_eq!(answers.special(), Some(SpecialChoice::NoneOfTheAbove));
+            assert_eq!(answers.choices().len(), 4);
+            assert_eq!(
+                answers.choices().last(),
+                Some(Choice::Special(SpecialChoice::NoneOfTheAbove))
+            );
+            assert_eq!(answers.select(answers.correct_index()), Some(true));
+            assert_eq!(answers.select(4), None);
+            if answers.correct() == Choice::Special(SpecialChoice::NoneOfTheAbove) {
+                assert!(!texts(&answers).contains(&"0answer"));
+                withheld += 1;
+            } else {
+                assert_eq!(answers.correct(), Choice::Text("0answer"));
+                assert_eq!(answers.select(3), Some(false));
+                shown += 1;
+            }
+        }
+        assert!(withheld > 0 && shown > withheld);
+
+        // Too few choices for a special choice.
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(question.mc_answers(2, &mut rng).unwrap().special(), None);
+
+        // Too few decoys, unless more are borrowed.
+        set.mc_cards[0].decoys = ["0decoy0"].into_iter().collect();
+        fn first_mc(set: &Set) -> Question<'_> {
+            set.questions(Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap()
+        }
+        for seed in 0..16 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let answers = first_mc(&set).mc_answers(4, &mut rng).unwrap();
+            assert_eq!(answers.special(), None);
+            assert_eq!(texts(&answers).len(), 2);
+        }
+        set.recall_mc.borrow_decoys = true;
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let answers = first_mc(&set).mc_answers(4, &mut rng).unwrap();
+        assert_eq!(answers.special(), Some(SpecialChoice::NoneOfTheAbove));
+        assert_eq!(answers.choice_count(), 4);
+    }
+
+    #[test]
+    fn mc_answers_all_of_the_above() {
+        let mut set = Set::example_recall_default();
+        set.recall_mc.all_of_the_above = 100;
+        set.mc_cards[0].answer.push_text("0other");
+        let questions = set
+            .questions(Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .collect::<Vec<_>>();
+        let mut all_correct = 0;
+        for seed in 0..64 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let answers = questions[0].mc_answers(4, &mut rng).unwrap();
+            assert_eq!(answers.special(), Some(SpecialChoice::AllOfTheAbove));
+            if answers.correct() == Choice::Special(SpecialChoice::AllOfTheAbove) {
+                let mut sorted = texts(&answers);
+                sorted.sort();
+                assert_eq!(sorted, ["0answer", "0other"]);
+                assert_eq!(answers.correct_index(), 2);
+                all_correct += 1;
+            } else {
+                assert_eq!(texts(&answers).len(), 3);
+            }
+
+            // Cards with one answer never get "All of the above".
+            let answers = questions[1].mc_answers(4, &mut rng).unwrap();
+            assert_eq!(answers.special(), None);
+        }
+        assert!(all_correct > 0);
     }
 
     #[test]
     fn mc_answers_small_set() {
         let set = Set::example_recall_default();
-        // Use deterministic RNG bc `Question::mc_answers` can return fewer
-        // results than expected in unlucky situations.
-        let mut rng = rand_chacha::ChaCha8Rng::from_seed(Default::default());
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
 
         let question = set
             .questions(Conditions {
@@ -574,7 +1526,7 @@ mod tests {
             .next()
             .unwrap();
         let answers = question.mc_answers(256, &mut rng).unwrap();
-        assert_eq!(answers.len(), 6);
+        assert_eq!(answers.choice_count(), 6);
 
         let question = set
             .questions(Conditions {
@@ -584,13 +1536,78 @@ mod tests {
             .next()
             .unwrap();
         let answers = question.mc_answers(256, &mut rng).unwrap();
-        assert_eq!(answers.len(), 4);
+        assert_eq!(answers.choice_count(), 4);
 
         let mut questions = set.questions(Conditions::INCLUDE_ALL);
         let answers = questions.next().unwrap().mc_answers(256, &mut rng).unwrap();
-        assert_eq!(answers.len(), 6);
+        assert_eq!(answers.choice_count(), 6);
         let answers = questions.last().unwrap().mc_answers(256, &mut rng).unwrap();
-        assert_eq!(answers.len(), 4);
+        assert_eq!(answers.choice_count(), 4);
+    }
+
+    #[test]
+    fn mc_answers_with_typos() {
+        let mut set = Set::example_recall_default();
+        set.recall_back.allowed_typos = Typos::Count(1);
+        set.recall_mc.allowed_typos = Typos::Count(1);
+        set.recall_mc.borrow_decoys = true;
+        set.flashcards = ["0", "1", "2", "3"]
+            .into_iter()
+            .map(|back| Flashcard::new(back.to_uppercase(), back))
+            .collect();
+        set.flashcards.push(Flashcard::new("feline", "cat"));
+        set.flashcards.push(Flashcard::new("vehicle", "car"));
+        for (card, answer) in set.mc_cards.iter_mut().zip(["a", "b", "c", "d"]) {
+            card.answer = answer.into();
+            card.decoys = Decoys::empty();
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut questions = set.questions(Conditions {
+            include_card_back: true,
+            include_mc: true,
+            ..Conditions::INCLUDE_NONE
+        });
+        // One character answers are all within a typo of each other, but
+        // are still different answers.
+        let answers = questions.next().unwrap().mc_answers(4, &mut rng).unwrap();
+        assert_eq!(answers.choice_count(), 4);
+        let cat = questions.nth(3).unwrap();
+        for _ in 0..16 {
+            let answers = cat.mc_answers(6, &mut rng).unwrap();
+            assert!(texts(&answers).contains(&"car"));
+        }
+        let answers = questions.last().unwrap().mc_answers(4, &mut rng).unwrap();
+        assert_eq!(answers.choice_count(), 4);
+    }
+
+    #[test]
+    fn mc_answers_duplicates() {
+        let mut set = Set::example_recall_default();
+        set.flashcards = ["0", "1", "1", "1", "1", "1", "1", "1", "2"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, back)| Flashcard::new(i.to_string(), back))
+            .collect();
+        set.flashcards[1].back = CardSide::new_multi(["colour", "color"]);
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        for seed in 0..64 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let answers = question.mc_answers(256, &mut rng).unwrap();
+            let mut sorted = texts(&answers);
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), answers.choice_count());
+            assert_eq!(answers.choice_count(), 4, "{answers:?}");
+            // Both ways of writing card 1's back are never choices together.
+            assert!(!(sorted.contains(&"colour") && sorted.contains(&"color")));
+            assert_eq!(question.mc_answers(3, &mut rng).unwrap().choice_count(), 3);
+        }
     }
 
     #[test]
@@ -639,6 +1656,108 @@ mod tests {
         }
     }
 
+    #[test]
+    fn questions_orders_len_matches_num_returned() {
+        let set = Set::example_recall_default();
+        let orders = [
+            QuestionOrder::Shuffled { seed: 1 },
+            QuestionOrder::RoundRobin,
+            QuestionOrder::Interleaved { seed: 1 },
+        ];
+        for order in orders {
+            for conditions in POSSIBLE_CONDITIONS {
+                let conditions = Conditions {
+                    order,
+                    ..conditions.clone()
+                };
+                let questions = set.questions(&conditions);
+                let grouped = set.questions(Conditions {
+                    order: QuestionOrder::Grouped,
+                    ..conditions.clone()
+                });
+                assert_eq!(questions.len(), grouped.len(), "Failed at {conditions:#?}");
+                let mut keys = questions.clone().map(|q| q.key()).collect::<Vec<_>>();
+                assert_eq!(keys.len(), questions.len(), "Failed at {conditions:#?}");
+                let mut grouped_keys = grouped.map(|q| q.key()).collect::<Vec<_>>();
+                keys.sort();
+                grouped_keys.sort();
+                assert_eq!(keys, grouped_keys, "Failed at {conditions:#?}");
+                assert_eq!(
+                    questions.clone().fold(0, |acc, _| acc + 1),
+                    questions.len(),
+                    "Failed at {conditions:#?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn questions_shuffled() {
+        let set = Set::example_recall_default();
+        let keys = |seed| {
+            set.questions(Conditions {
+                order: QuestionOrder::Shuffled { seed },
+                ..Conditions::INCLUDE_ALL
+            })
+            .map(|question| question.key())
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(5), keys(5));
+        assert_ne!(keys(5), keys(6));
+
+        // The generator is portable, so seeds give the same order everywhere.
+        let answers = set
+            .questions(Conditions {
+                order: QuestionOrder::Shuffled { seed: 5 },
+                ..Conditions::INCLUDE_ALL
+            })
+            .map(|question| question.answers().next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            [
+                "1", "1answer", "0answer", "5", "2", "f", "4", "b", "0", "c", "d", "2answer", "a",
+                "e", "3answer", "3"
+            ]
+        );
+    }
+
+    #[test]
+    fn questions_round_robin() {
+        let set = Set::example_recall_default();
+        let sides = set
+            .questions(Conditions {
+                order: QuestionOrder::RoundRobin,
+                ..Conditions::INCLUDE_ALL
+            })
+            .map(|question| question.key().side)
+            .collect::<Vec<_>>();
+        let (back, front) = (Some(Side::Back), Some(Side::Front));
+        assert_eq!(sides[..6], [back, front, None, back, front, None]);
+        assert_eq!(sides[12..], [back, front, back, front]);
+    }
+
+    #[test]
+    fn questions_interleaved() {
+        let set = Set::example_recall_default();
+        let keys = set
+            .questions(Conditions {
+                order: QuestionOrder::Interleaved { seed: 2 },
+                include_mc: false,
+                ..Conditions::INCLUDE_ALL
+            })
+            .map(|question| question.key())
+            .collect::<Vec<_>>();
+        for (i, key) in keys.iter().enumerate() {
+            let distance = keys
+                .iter()
+                .position(|other| other.card == key.card && other.side != key.side)
+                .unwrap()
+                .abs_diff(i);
+            assert!(distance >= 5, "{distance}");
+        }
+    }
+
     #[test]
     fn questions_fold() {
         let set = Set::example_recall_default();
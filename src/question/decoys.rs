@@ -0,0 +1,258 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use rand::{seq::SliceRandom, Rng};
+use smartstring::alias::String;
+
+use crate::card::CardSide;
+
+/// How much each way of comparing a decoy to the correct answer counts
+/// towards [`similarity`].  These add up to 1.
+const LENGTH_WEIGHT: f64 = 0.25;
+const OVERLAP_WEIGHT: f64 = 0.35;
+const TAGS_WEIGHT: f64 = 0.2;
+const SCRIPT_WEIGHT: f64 = 0.1;
+const NUMBER_WEIGHT: f64 = 0.1;
+
+/// Picks `count` different decoys at random from the text on `sides`,
+/// skipping text that `is_decoy` rejects, or every such decoy if there are
+/// fewer.  At most one text is taken from each side, so two ways of writing
+/// the same answer are never both choices.
+///
+/// Partially shuffles the candidate texts, visiting each at most once, so
+/// a decoy is found for every side that has one.
+pub(crate) fn random_decoys<'a, R: Rng + ?Sized>(
+    sides: impl IntoIterator<Item = &'a CardSide>,
+    count: usize,
+    is_decoy: impl Fn(&str) -> bool,
+    rng: &mut R,
+) -> Vec<&'a str> {
+    let mut candidates = sides
+        .into_iter()
+        .enumerate()
+        .flat_map(|(side, texts)| texts.iter_text().map(move |text| (side, text)))
+        .collect::<Vec<_>>();
+    let mut decoys = Vec::with_capacity(count);
+    let mut used_sides = HashSet::new();
+    let mut seen = HashSet::new();
+    for i in 0..candidates.len() {
+        if decoys.len() == count {
+            break;
+        }
+        let j = rng.gen_range(i..candidates.len());
+        candidates.swap(i, j);
+
+        let (side, text) = candidates[i];
+        if !used_sides.contains(&side) && is_decoy(text) && seen.insert(text) {
+            used_sides.insert(side);
+            decoys.push(text);
+        }
+    }
+    decoys
+}
+
+/// Picks up to `count` different decoys from `candidates` that are most
+/// similar to `answer`, breaking ties randomly.  Each candidate comes with the
+/// tags of its card.
+pub(crate) fn most_similar<'a, R: Rng + ?Sized>(
+    answer: &str,
+    answer_tags: &[String],
+    candidates: impl IntoIterator<Item = (&'a str, &'a [String])>,
+    count: usize,
+    rng: &mut R,
+) -> Vec<&'a str> {
+    let mut candidates = candidates
+        .into_iter()
+        .map(|(text, tags)| (similarity(answer, answer_tags, text, tags), text))
+        .collect::<Vec<_>>();
+    candidates.shuffle(rng);
+    candidates.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+    let mut decoys = Vec::with_capacity(count);
+    for (_, text) in candidates {
+        if decoys.len() == count {
+            break;
+        }
+        if !decoys.contains(&text) {
+            decoys.push(text);
+        }
+    }
+    decoys
+}
+
+/// How similar `decoy` is to `answer`, from 0 to 1.
+fn similarity(answer: &str, answer_tags: &[String], decoy: &str, decoy_tags: &[String]) -> f64 {
+    let same = |a: bool| if a { 1.0 } else { 0.0 };
+    LENGTH_WEIGHT * length_similarity(answer, decoy)
+        + OVERLAP_WEIGHT * bigram_overlap(answer, decoy)
+        + TAGS_WEIGHT * tag_overlap(answer_tags, decoy_tags)
+        + SCRIPT_WEIGHT * same(Script::of(answer) == Script::of(decoy))
+        + NUMBER_WEIGHT * same(is_number(answer) == is_number(decoy))
+}
+
+/// Ratio of the shorter length to the longer one.
+fn length_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (a.chars().count(), b.chars().count());
+    if a == b {
+        return 1.0;
+    }
+    a.min(b) as f64 / a.max(b) as f64
+}
+
+/// Dice coefficient of the pairs of adjacent characters in each text,
+/// ignoring case.  The start and end of the text count as characters so
+/// single characters can be compared.
+fn bigram_overlap(a: &str, b: &str) -> f64 {
+    fn bigrams(s: &str) -> Vec<(char, char)> {
+        let chars = [' ']
+            .into_iter()
+            .chain(s.chars().flat_map(char::to_lowercase))
+            .chain([' '])
+            .collect::<Vec<_>>();
+        let mut bigrams = chars
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        bigrams.sort_unstable();
+        bigrams.dedup();
+        bigrams
+    }
+
+    let (a, b) = (bigrams(a), bigrams(b));
+    let shared = a
+        .iter()
+        .filter(|bigram| b.binary_search(bigram).is_ok())
+        .count();
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// Fraction of all the tags that both cards have.
+fn tag_overlap(a: &[String], b: &[String]) -> f64 {
+    let shared = a.iter().filter(|tag| b.contains(tag)).count();
+    let total = a.len() + b.len() - shared;
+    if total == 0 {
+        return 0.0;
+    }
+    shared as f64 / total as f64
+}
+
+/// Whether `s` is a number such as "1,024", "-3.5", or "12:30".
+fn is_number(s: &str) -> bool {
+    s.chars().any(|c| c.is_numeric())
+        && s.chars()
+            .all(|c| c.is_numeric() || c.is_whitespace() || ".,-+/:%".contains(c))
+}
+
+/// Writing system of text, judged by its first letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+    /// A script not listed above, identified by the 128 character block its
+    /// first letter is in.
+    Other(u32),
+}
+
+impl Script {
+    /// The script of the first letter in `s`, or `None` if it has no letters.
+    fn of(s: &str) -> Option<Self> {
+        let c = s.chars().find(|c| c.is_alphabetic())? as u32;
+        Some(match c {
+            0..=0x24f | 0x1e00..=0x1eff => Script::Latin,
+            0x370..=0x3ff | 0x1f00..=0x1fff => Script::Greek,
+            0x400..=0x52f => Script::Cyrillic,
+            0x530..=0x58f => Script::Armenian,
+            0x590..=0x5ff => Script::Hebrew,
+            0x600..=0x6ff | 0x750..=0x77f => Script::Arabic,
+            0x900..=0x97f => Script::Devanagari,
+            0xe00..=0xe7f => Script::Thai,
+            0x1100..=0x11ff | 0x3130..=0x318f | 0xac00..=0xd7af => Script::Hangul,
+            0x3040..=0x30ff => Script::Kana,
+            0x3400..=0x4dbf | 0x4e00..=0x9fff => Script::Han,
+            c => Script::Other(c >> 7),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn similarity_parts() {
+        assert_eq!(length_similarity("abcd", "ab"), 0.5);
+        assert_eq!(bigram_overlap("Paris", "paris"), 1.0);
+        assert_eq!(bigram_overlap("ab", "cd"), 0.0);
+        assert!(bigram_overlap("Paris", "Parma") > bigram_overlap("Paris", "Rome"));
+        let tags = |tags: &[&str]| tags.iter().map(|&tag| tag.into()).collect::<Vec<String>>();
+        assert_eq!(
+            tag_overlap(&tags(&["a", "b"]), &tags(&["b", "c"])),
+            1.0 / 3.0
+        );
+        assert_eq!(tag_overlap(&[], &[]), 0.0);
+        assert!(is_number("1,024") && is_number("-3.5") && !is_number("3rd"));
+        assert_eq!(Script::of("3 Äpfel"), Some(Script::Latin));
+        assert_eq!(Script::of("Москва"), Some(Script::Cyrillic));
+        assert_eq!(Script::of("東京"), Some(Script::Han));
+        assert_eq!(Script::of("42"), None);
+    }
+
+    #[test]
+    fn random_decoys_exhaustive() {
+        let mut sides = ["a", "b", "b", "b", "b", "b", "b", "x", "c"]
+            .map(CardSide::from)
+            .to_vec();
+        sides[8].push_text("d");
+        for seed in 0..64 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+            let mut decoys = random_decoys(&sides, 8, |text| text != "x", &mut rng);
+            decoys.sort_unstable();
+            assert!(decoys == ["a", "b", "c"] || decoys == ["a", "b", "d"]);
+            assert_eq!(
+                random_decoys(&sides, 2, |text| text != "x", &mut rng).len(),
+                2
+            );
+        }
+    }
+
+    #[test]
+    fn random_decoys_one_per_side() {
+        let mut sides = ["colour", "x"].map(CardSide::from).to_vec();
+        sides[0].push_text("color");
+        for seed in 0..16 {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+            let decoys = random_decoys(&sides, 3, |text| text != "color", &mut rng);
+            assert_eq!(decoys.len(), 2);
+            assert!(decoys.contains(&"colour") && decoys.contains(&"x"));
+        }
+    }
+
+    #[test]
+    fn most_similar_decoys() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        let capitals = ["capitals".into()];
+        let candidates = [
+            ("photosynthesis", &[][..]),
+            ("1789", &[][..]),
+            ("Москва", &capitals[..]),
+            ("Parma", &[][..]),
+            ("Rome", &capitals[..]),
+            ("Rome", &capitals[..]),
+        ];
+        assert_eq!(
+            most_similar("Paris", &capitals, candidates, 3, &mut rng),
+            ["Parma", "Rome", "Москва"]
+        );
+        assert_eq!(most_similar("1815", &[], candidates, 1, &mut rng), ["1789"]);
+    }
+}
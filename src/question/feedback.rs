@@ -0,0 +1,350 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::card::{
+    matching::{self, Edit, PunctuationMode},
+    RecallSettings,
+};
+
+use super::Question;
+
+impl<'a> Question<'a> {
+    /// Lines `answer` up with the closest correct answer to this question so
+    /// frontends can highlight exactly what is wrong with it.  Answers with
+    /// optional parts are lined up using the way of writing them closest to
+    /// `answer`.
+    ///
+    /// Works on graphemes, so a letter and its accents are never split apart.
+    /// Differences the rules for this question ignore, such as capitalization
+    /// when caps aren't checked or ignored punctuation, are not counted as
+    /// differences.  Ignored graphemes are part of the span before them.
+    ///
+    /// Returns `None` if this question has no text answers.
+    pub fn feedback(&self, answer: &str) -> Option<AnswerDiff<'a>> {
+        let (answer_side, rules) = self.answer_side();
+        let modes = rules.punctuation_modes();
+        let typed = modes
+            .iter()
+            .map(|&mode| Graphemes::new(rules, answer, mode))
+            .collect::<Vec<_>>();
+
+        answer_side
+            .iter_expansions()
+            .flat_map(|(variant, expected_text)| {
+                modes.iter().zip(&typed).map(move |(&mode, typed)| {
+                    let expected = Graphemes::new(rules, expected_text, mode);
+                    let edits = matching::align(&expected.keys, &typed.keys);
+                    let distance = edits.iter().filter(|&&edit| edit != Edit::Keep).count();
+                    let diff = AnswerDiff {
+                        variant,
+                        expected: expected_text,
+                        spans: spans(&edits, &expected, typed),
+                    };
+                    (distance, diff)
+                })
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, diff)| diff)
+    }
+}
+
+/// The result of lining up a typed answer with the text it should have been.
+///
+/// Created by [`Question::feedback`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnswerDiff<'a> {
+    /// Index of the text on the card the answer was lined up with.
+    pub variant: usize,
+    /// The text the answer was lined up with.  For answers with optional
+    /// parts, this is one way of writing it rather than the text shown.
+    pub expected: &'a str,
+    /// Pieces of the expected and typed text in order.  Together they cover
+    /// both, except for whitespace at the start and end.
+    pub spans: Vec<DiffSpan>,
+}
+
+impl<'a> AnswerDiff<'a> {
+    /// Whether the typed answer has no differences from the expected text.
+    pub fn is_same(&self) -> bool {
+        self.spans.iter().all(|span| span.kind == DiffKind::Same)
+    }
+}
+
+/// A piece of an [`AnswerDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSpan {
+    /// How this piece of the typed answer differs.
+    pub kind: DiffKind,
+    /// Byte range of this in the expected text.  Empty for
+    /// [`DiffKind::Inserted`].
+    pub expected: Range<usize>,
+    /// Byte range of this in the typed answer.  Empty for
+    /// [`DiffKind::Deleted`].
+    pub typed: Range<usize>,
+}
+
+/// How a piece of a typed answer differs from the expected text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Typed correctly.
+    Same,
+    /// Typed but not in the expected text.
+    Inserted,
+    /// In the expected text but not typed.
+    Deleted,
+    /// Typed as something else.
+    Substituted,
+}
+
+/// Text split into graphemes along with the form each is compared in.
+struct Graphemes {
+    /// Byte ranges of each grapheme.
+    ranges: Vec<Range<usize>>,
+    keys: Vec<std::string::String>,
+}
+
+impl Graphemes {
+    /// Splits `text`, leaving out graphemes that aren't compared with
+    /// `punctuation` and the rules, such as ignored punctuation or repeated
+    /// whitespace.  Each range covers the graphemes left out after it.
+    fn new(rules: &RecallSettings, text: &str, punctuation: PunctuationMode) -> Self {
+        let start = text.len() - text.trim_start().len();
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut keys: Vec<std::string::String> = Vec::new();
+        // Start of graphemes left out before the first one compared.
+        let mut leading = None;
+        for (index, grapheme) in text.trim().grapheme_indices(true) {
+            let range = start + index..start + index + grapheme.len();
+            let key = rules.grapheme_key(grapheme, punctuation).filter(|key| {
+                // Whitespace, including punctuation replaced with spaces, is
+                // trimmed and collapsed if the rules say so.
+                !is_space(key)
+                    || keys
+                        .last()
+                        .is_some_and(|last| !(rules.collapse_whitespace && is_space(last)))
+            });
+            match (key, ranges.last_mut()) {
+                (Some(key), _) => {
+                    ranges.push(leading.take().unwrap_or(range.start)..range.end);
+                    keys.push(key);
+                }
+                (None, Some(last)) => last.end = range.end,
+                (None, None) => {
+                    leading.get_or_insert(range.start);
+                }
+            }
+        }
+        while keys.last().is_some_and(|last| is_space(last)) {
+            keys.pop();
+            let end = ranges.pop().map_or(0, |range| range.end);
+            if let Some(last) = ranges.last_mut() {
+                last.end = end;
+            }
+        }
+        Self { ranges, keys }
+    }
+
+    /// Byte range covering graphemes `from..to`.
+    fn range(&self, from: usize, to: usize) -> Range<usize> {
+        let start = match self.ranges.get(from) {
+            Some(range) => range.start,
+            None => self.ranges.last().map_or(0, |range| range.end),
+        };
+        let end = match to {
+            0 => start,
+            to => self.ranges[to - 1].end.max(start),
+        };
+        start..end
+    }
+}
+
+fn is_space(key: &str) -> bool {
+    key.chars().all(char::is_whitespace)
+}
+
+/// Merges runs of the same kind of edit into spans.
+fn spans(edits: &[Edit], expected: &Graphemes, typed: &Graphemes) -> Vec<DiffSpan> {
+    let mut spans = Vec::new();
+    let (mut expected_index, mut typed_index) = (0, 0);
+    for run in edits.chunk_by(|a, b| a == b) {
+        let (kind, expected_len, typed_len) = match run[0] {
+            Edit::Keep => (DiffKind::Same, run.len(), run.len()),
+            Edit::Insert => (DiffKind::Inserted, 0, run.len()),
+            Edit::Delete => (DiffKind::Deleted, run.len(), 0),
+            Edit::Substitute => (DiffKind::Substituted, run.len(), run.len()),
+        };
+        spans.push(DiffSpan {
+            kind,
+            expected: expected.range(expected_index, expected_index + expected_len),
+            typed: typed.range(typed_index, typed_index + typed_len),
+        });
+        expected_index += expected_len;
+        typed_index += typed_len;
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::{CardSide, OptionalAnswer, Punctuation, Set},
+        question::Conditions,
+    };
+
+    use super::*;
+
+    fn span(kind: DiffKind, expected: Range<usize>, typed: Range<usize>) -> DiffSpan {
+        DiffSpan {
+            kind,
+            expected,
+            typed,
+        }
+    }
+
+    fn back_question(set: &Set) -> Question<'_> {
+        set.questions(Conditions {
+            include_card_back: true,
+            ..Conditions::INCLUDE_NONE
+        })
+        .next()
+        .unwrap()
+    }
+
+    #[test]
+    fn feedback_same() {
+        let mut set = Set::example_recall_default();
+        set.flashcards[0].back = CardSide::new("Paris");
+        let diff = back_question(&set).feedback(" paris ").unwrap();
+        assert_eq!(diff.expected, "Paris");
+        assert_eq!(diff.spans, [span(DiffKind::Same, 0..5, 1..6)]);
+        assert!(diff.is_same());
+    }
+
+    #[test]
+    fn feedback_edits() {
+        let mut set = Set::example_recall_default();
+        set.flashcards[0].back = CardSide::new("receive");
+        let diff = back_question(&set).feedback("recieve").unwrap();
+        assert_eq!(
+            diff.spans,
+            [
+                span(DiffKind::Same, 0..3, 0..3),
+                span(DiffKind::Substituted, 3..5, 3..5),
+                span(DiffKind::Same, 5..7, 5..7),
+            ]
+        );
+
+        let diff = back_question(&set).feedback("receve").unwrap();
+        assert_eq!(
+            diff.spans,
+            [
+                span(DiffKind::Same, 0..4, 0..4),
+                span(DiffKind::Deleted, 4..5, 4..4),
+                span(DiffKind::Same, 5..7, 4..6),
+            ]
+        );
+
+        let diff = back_question(&set).feedback("receiXve").unwrap();
+        assert_eq!(
+            diff.spans,
+            [
+                span(DiffKind::Same, 0..5, 0..5),
+                span(DiffKind::Inserted, 5..5, 5..6),
+                span(DiffKind::Same, 5..7, 6..8),
+            ]
+        );
+
+        let diff = back_question(&set).feedback("reXeive").unwrap();
+        assert_eq!(
+            diff.spans,
+            [
+                span(DiffKind::Same, 0..2, 0..2),
+                span(DiffKind::Substituted, 2..3, 2..3),
+                span(DiffKind::Same, 3..7, 3..7),
+            ]
+        );
+        assert!(!diff.is_same());
+    }
+
+    #[test]
+    fn feedback_graphemes() {
+        let mut set = Set::example_recall_default();
+        set.flashcards[0].back = CardSide::new("nin\u{303}o");
+        let diff = back_question(&set).feedback("nino").unwrap();
+        assert_eq!(
+            diff.spans,
+            [
+                span(DiffKind::Same, 0..2, 0..2),
+                span(DiffKind::Substituted, 2..5, 2..3),
+                span(DiffKind::Same, 5..6, 3..4),
+            ]
+        );
+    }
+
+    #[test]
+    fn feedback_ignored_punctuation() {
+        let mut set = Set::example_recall_default();
+        set.recall_back.ignore_punctuation = Punctuation::All;
+        set.flashcards[0].back = CardSide::new("Dr. Who");
+        let diff = back_question(&set).feedback("Dr Who").unwrap();
+        assert_eq!(diff.spans, [span(DiffKind::Same, 0..7, 0..6)]);
+        set.flashcards[0].back = CardSide::new("well-known");
+        assert!(back_question(&set)
+            .feedback("well known")
+            .unwrap()
+            .is_same());
+        assert!(back_question(&set).feedback("wellknown").unwrap().is_same());
+
+        set.flashcards[0].back = CardSide::new("well-know");
+        let diff = back_question(&set).feedback("well know.").unwrap();
+        assert!(diff.is_same());
+        let diff = back_question(&set).feedback("wel know").unwrap();
+        assert_eq!(
+            diff.spans,
+            [
+                span(DiffKind::Same, 0..3, 0..3),
+                span(DiffKind::Substituted, 3..5, 3..4),
+                span(DiffKind::Same, 5..9, 4..8),
+            ]
+        );
+    }
+
+    #[test]
+    fn feedback_collapsed_whitespace() {
+        let mut set = Set::example_recall_default();
+        set.flashcards[0].back = CardSide::new("a b");
+        assert!(!back_question(&set).feedback("a  b").unwrap().is_same());
+        set.recall_back.collapse_whitespace = true;
+        let diff = back_question(&set).feedback("a \t b").unwrap();
+        assert_eq!(diff.spans, [span(DiffKind::Same, 0..3, 0..5)]);
+    }
+
+    #[test]
+    fn feedback_closest_variant() {
+        let mut set = Set::example_recall_default();
+        set.flashcards[0].back = CardSide::new_multi(["colour", "gray"]);
+        let diff = back_question(&set).feedback("grey").unwrap();
+        assert_eq!(diff.variant, 1);
+        assert_eq!(diff.expected, "gray");
+
+        set.flashcards[0].back = CardSide::empty();
+        assert_eq!(back_question(&set).feedback("grey"), None);
+    }
+
+    #[test]
+    fn feedback_optional() {
+        let mut set = Set::example_recall_default();
+        let mut back = CardSide::new("walk");
+        back.push_optional(OptionalAnswer::new("(to) run"));
+        set.flashcards[0].back = back;
+        let diff = back_question(&set).feedback("run").unwrap();
+        assert_eq!(diff.variant, 1);
+        assert_eq!(diff.expected, "run");
+        assert!(diff.is_same());
+
+        let diff = back_question(&set).feedback("to rum").unwrap();
+        assert_eq!(diff.variant, 1);
+        assert_eq!(diff.expected, "to run");
+    }
+}
@@ -0,0 +1,174 @@
+use std::{borrow::Borrow, cmp::Ordering};
+
+use crate::{
+    card::Set,
+    progress::History,
+    schedule::{Grade, Review, Timestamp},
+};
+
+use super::{Conditions, Questions};
+
+/// Days after which a review counts half as much as one at the time of the
+/// latest review when ranking weak questions.
+const RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+/// Weight of an imaginary correct answer at the time of the latest review
+/// added to every question, so old mistakes rank below recent ones rather
+/// than making the error rate 1 on their own.
+const PRIOR_WEIGHT: f64 = 1.0;
+
+impl Set {
+    /// Up to `count` of the questions from this set matching `conditions`
+    /// that have been answered wrong most often recently according to
+    /// `history`, weakest first.  Equally weak questions are in the order
+    /// given by `conditions`.
+    ///
+    /// Questions are ranked by their error rate, with each answer counting
+    /// half as much for every week it is older than the latest review in
+    /// `history` so questions that have since been learned drop out.  Every
+    /// question also counts as having been answered right once at the time of
+    /// the latest review, so a question missed once long ago ranks below one
+    /// missed once recently.
+    /// Questions that have never been answered, or never been answered wrong,
+    /// are not included.
+    pub fn weak_questions(
+        &self,
+        history: &History,
+        conditions: impl Borrow<Conditions>,
+        count: usize,
+    ) -> Questions<'_> {
+        let Some(latest) = history
+            .iter()
+            .filter_map(|(_, reviews)| Some(reviews.last()?.at))
+            .max()
+        else {
+            return Questions::from_ordered(self, Vec::new());
+        };
+        let mut weak = self
+            .questions(conditions)
+            .filter_map(|question| {
+                let error_rate = recent_error_rate(history.reviews(question.key()), latest)?;
+                (error_rate > 0.0).then_some((error_rate, question))
+            })
+            .collect::<Vec<_>>();
+        weak.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        weak.truncate(count);
+        Questions::from_ordered(
+            self,
+            weak.into_iter().map(|(_, question)| question).collect(),
+        )
+    }
+}
+
+/// Fraction of `reviews` that were wrong, weighted towards those closest to
+/// `latest` and including [`PRIOR_WEIGHT`], or `None` if there are none.
+fn recent_error_rate(reviews: &[Review], latest: Timestamp) -> Option<f64> {
+    if reviews.is_empty() {
+        return None;
+    }
+    let (mut errors, mut total) = (0.0, 0.0);
+    for review in reviews {
+        let weight = 0.5_f64.powf(latest.days_since(review.at) / RECENCY_HALF_LIFE_DAYS);
+        if review.grade == Grade::Again {
+            errors += weight;
+        }
+        total += weight;
+    }
+    Some(errors / (total + PRIOR_WEIGHT))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{card::Side, question::QuestionKey};
+
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    /// Reviews with the given grades, one on each day from `first_day`.
+    fn reviews(first_day: u64, grades: &[Grade]) -> Vec<Review> {
+        grades
+            .iter()
+            .zip(first_day..)
+            .map(|(&grade, day)| Review {
+                at: Timestamp::from_unix_secs(day * DAY),
+                grade,
+                response_time: None,
+                verdict: None,
+                recall: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn error_rate_weighted() {
+        use Grade::*;
+        let latest = Timestamp::from_unix_secs(100 * DAY);
+        assert_eq!(recent_error_rate(&[], latest), None);
+        assert_eq!(
+            recent_error_rate(&reviews(99, &[Good, Good]), latest),
+            Some(0.0)
+        );
+        // The prior counts as one right answer at `latest`.
+        assert_eq!(
+            recent_error_rate(&reviews(100, &[Again]), latest),
+            Some(0.5)
+        );
+        let old_miss = recent_error_rate(&reviews(99, &[Again, Good]), latest).unwrap();
+        let new_miss = recent_error_rate(&reviews(99, &[Good, Again]), latest).unwrap();
+        assert!(old_miss < new_miss);
+        // A week makes a review count half as much.
+        let mut spaced = reviews(93, &[Again]);
+        spaced.extend(reviews(100, &[Good]));
+        let rate = recent_error_rate(&spaced, latest).unwrap();
+        assert!((rate - 0.5 / 2.5).abs() < 1e-9);
+        // A single miss long ago is far from a single miss now.
+        let long_ago = recent_error_rate(&reviews(40, &[Again]), latest).unwrap();
+        assert!(long_ago < 0.01);
+    }
+
+    #[test]
+    fn weak_questions_ranked() {
+        use Grade::*;
+        let set = Set::example_recall_default();
+        let back = |index: usize| QuestionKey {
+            card: set.flashcards[index].id(),
+            side: Some(Side::Back),
+        };
+        let mut history = History::new();
+        let mut push = |key, first_day, grades: &[Grade]| {
+            for review in reviews(first_day, grades) {
+                history.push(key, review);
+            }
+        };
+        // Missed once, long before the latest review.
+        push(back(0), 0, &[Again]);
+        push(back(1), 30, &[Good, Again, Again]);
+        push(back(2), 30, &[Good, Good]);
+        push(back(3), 31, &[Good, Again]);
+        // Missed, then right a month later.
+        push(back(4), 0, &[Again]);
+        push(back(4), 31, &[Good]);
+        // Missed once, at the latest review.
+        push(back(5), 32, &[Again]);
+
+        let conditions = Conditions {
+            include_card_back: true,
+            ..Conditions::INCLUDE_NONE
+        };
+        let keys = set
+            .weak_questions(&history, &conditions, 10)
+            .map(|question| question.key())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, [back(1), back(5), back(3), back(0), back(4)]);
+        assert_eq!(set.weak_questions(&history, &conditions, 1).len(), 1);
+        assert_eq!(
+            set.weak_questions(&history, Conditions::INCLUDE_NONE, 10)
+                .len(),
+            0
+        );
+        assert_eq!(
+            set.weak_questions(&History::new(), &conditions, 10).len(),
+            0
+        );
+    }
+}
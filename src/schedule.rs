@@ -0,0 +1,473 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::{Add, Sub},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    card::RecallType,
+    progress::History,
+    question::{AnswerVerdict, Question, QuestionKey, VerdictKind},
+};
+
+mod fsrs;
+mod sm2;
+
+pub use fsrs::{Fsrs, FsrsState};
+pub use sm2::{Sm2, Sm2State};
+
+/// Seconds in a day.
+const DAY: u64 = 24 * 60 * 60;
+
+/// A point in time, in whole seconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// The time `secs` seconds after the Unix epoch.
+    pub const fn from_unix_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    /// Seconds since the Unix epoch.
+    pub fn unix_secs(self) -> u64 {
+        self.0
+    }
+
+    /// The time `days` days after this.
+    pub(crate) fn add_days(self, days: u32) -> Self {
+        Self(self.0.saturating_add(u64::from(days) * DAY))
+    }
+
+    /// Days from `earlier` to this, including fractions of days.
+    pub(crate) fn days_since(self, earlier: Self) -> f64 {
+        (self - earlier).as_secs_f64() / DAY as f64
+    }
+}
+
+impl Add<Duration> for Timestamp {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0.saturating_add(rhs.as_secs()))
+    }
+}
+
+impl Sub for Timestamp {
+    type Output = Duration;
+
+    /// Time from `rhs` to this, or zero if `rhs` is later.
+    fn sub(self, rhs: Self) -> Self::Output {
+        Duration::from_secs(self.0.saturating_sub(rhs.0))
+    }
+}
+
+/// Tells a [`Schedule`] what time it is.
+///
+/// Any `Fn() -> Timestamp` is a clock, which lets tests control time.
+pub trait Clock {
+    /// The current time.
+    fn now(&self) -> Timestamp;
+}
+
+/// Clock reading the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        let since_epoch = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        Timestamp(since_epoch.as_secs())
+    }
+}
+
+impl<F: Fn() -> Timestamp> Clock for F {
+    fn now(&self) -> Timestamp {
+        self()
+    }
+}
+
+/// How well the player remembered the answer to a question.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Grade {
+    /// Forgot the answer.
+    Again,
+    /// Remembered the answer, but with difficulty or small mistakes.
+    Hard,
+    /// Remembered the answer.
+    Good,
+    /// Remembered the answer without any effort.
+    Easy,
+}
+
+impl Grade {
+    /// The grade for an answer checked by [`Question::check_answer`].
+    ///
+    /// Exactly correct answers are [`Grade::Good`], answers accepted with
+    /// leniency are [`Grade::Hard`], and anything else is [`Grade::Again`].
+    pub fn from_verdict(verdict: &AnswerVerdict<'_>) -> Self {
+        match verdict {
+            AnswerVerdict::Correct { .. } => Self::Good,
+            AnswerVerdict::Accepted { .. } => Self::Hard,
+            _ => Self::Again,
+        }
+    }
+}
+
+/// One answer to a question.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Review {
+    /// When the question was answered.
+    pub at: Timestamp,
+    /// How well the answer was remembered.
+    pub grade: Grade,
+    /// How long the player took to answer, if known.
+    pub response_time: Option<Duration>,
+    /// How the answer was judged, if it was checked by
+    /// [`Question::check_answer`] rather than graded by the player.
+    pub verdict: Option<VerdictKind>,
+    /// How the question was asked, if known.
+    pub recall: Option<RecallType>,
+}
+
+/// A policy for deciding when questions should next be reviewed, such as
+/// [`Sm2`] or [`Fsrs`].
+pub trait Scheduler {
+    /// What is remembered about a question between reviews.
+    type State: Clone + Debug;
+
+    /// Name of this scheduler in progress files, such as "sm2".  Saved states
+    /// are only used by schedulers with the same name.
+    const NAME: &'static str;
+
+    /// The state of a question after `review`, given its state after the
+    /// reviews before it, or `None` if this is its first review.
+    fn review(&self, previous: Option<&Self::State>, review: Review) -> Self::State;
+
+    /// When a question in `state` should next be reviewed.
+    fn due(&self, state: &Self::State) -> Timestamp;
+
+    /// Writes `state` as a single line for a progress file.
+    fn save_state(&self, state: &Self::State) -> String;
+
+    /// Reads a state written by [`Scheduler::save_state`], or `None` if it
+    /// isn't valid.
+    fn load_state(&self, s: &str) -> Option<Self::State>;
+}
+
+/// Keeps track of every review of each question and when each question should
+/// next be reviewed according to a [`Scheduler`].
+///
+/// Questions are identified by [`Question::key`], so the same schedule can be
+/// used with questions from different loads of the same set.
+#[derive(Debug, Clone)]
+pub struct Schedule<S: Scheduler, C> {
+    scheduler: S,
+    clock: C,
+    history: History,
+    states: HashMap<QuestionKey, S::State>,
+}
+
+impl<S: Scheduler, C: Clock> Schedule<S, C> {
+    /// An empty schedule where no question has been reviewed yet.
+    pub fn new(scheduler: S, clock: C) -> Self {
+        let mut history = History::new();
+        history.set_scheduler(S::NAME);
+        Self {
+            scheduler,
+            clock,
+            history,
+            states: HashMap::new(),
+        }
+    }
+
+    /// A schedule with the reviews and scheduler states in `history`, such as
+    /// a [`History`] loaded from a progress file.
+    ///
+    /// Saved states are used as they are if they were saved by a scheduler
+    /// with the same [`Scheduler::NAME`], so changing the scheduler's settings
+    /// doesn't change when questions reviewed before are due.  The states of
+    /// other questions are rebuilt by replaying their reviews.
+    pub fn from_progress(scheduler: S, clock: C, history: History) -> Self {
+        let mut schedule = Self::new(scheduler, clock);
+        let saved = history.scheduler() == Some(S::NAME);
+        for (key, reviews) in history.iter() {
+            let state = history
+                .state(key)
+                .filter(|_| saved)
+                .and_then(|state| schedule.scheduler.load_state(state));
+            match state {
+                Some(state) => {
+                    for &review in reviews {
+                        schedule.history.push(key, review);
+                    }
+                    schedule.set_state(key, state);
+                }
+                None => {
+                    for &review in reviews {
+                        schedule.insert(key, review);
+                    }
+                }
+            }
+        }
+        schedule
+    }
+
+    /// A schedule with the reviews in `history`, which must be in the order
+    /// they happened for each question.  Scheduler states are rebuilt by
+    /// replaying the reviews; see [`Schedule::from_progress`] to use saved
+    /// ones.
+    pub fn from_history(
+        scheduler: S,
+        clock: C,
+        history: impl IntoIterator<Item = (QuestionKey, Review)>,
+    ) -> Self {
+        let mut schedule = Self::new(scheduler, clock);
+        for (key, review) in history {
+            schedule.insert(key, review);
+        }
+        schedule
+    }
+
+    /// This schedule with the same reviews, but scheduled by `scheduler`
+    /// instead.
+    pub fn with_scheduler<T: Scheduler>(self, scheduler: T) -> Schedule<T, C> {
+        Schedule::from_history(scheduler, self.clock, self.history)
+    }
+
+    /// The scheduler deciding when questions are due.
+    pub fn scheduler(&self) -> &S {
+        &self.scheduler
+    }
+
+    /// Records that `question` was answered now with the given grade.
+    pub fn review(&mut self, question: &Question<'_>, grade: Grade) {
+        self.review_timed(question, grade, None);
+    }
+
+    /// Records that `question` was answered now with the given grade, taking
+    /// `response_time` to answer.
+    pub fn review_timed(
+        &mut self,
+        question: &Question<'_>,
+        grade: Grade,
+        response_time: Option<Duration>,
+    ) {
+        let review = Review {
+            at: self.clock.now(),
+            grade,
+            response_time,
+            verdict: None,
+            recall: None,
+        };
+        self.insert(question.key(), review);
+    }
+
+    /// Records that `question` was asked as `recall` and answered now, taking
+    /// `response_time` to answer, graded by [`Grade::from_verdict`].  The kind
+    /// of verdict and how the question was asked are kept with the review.
+    pub fn review_answer(
+        &mut self,
+        question: &Question<'_>,
+        recall: RecallType,
+        verdict: &AnswerVerdict<'_>,
+        response_time: Option<Duration>,
+    ) {
+        let review = Review {
+            at: self.clock.now(),
+            grade: Grade::from_verdict(verdict),
+            response_time,
+            verdict: Some(verdict.kind()),
+            recall: Some(recall),
+        };
+        self.insert(question.key(), review);
+    }
+
+    fn insert(&mut self, key: QuestionKey, review: Review) {
+        let state = self.scheduler.review(self.states.get(&key), review);
+        self.history.push(key, review);
+        self.set_state(key, state);
+    }
+
+    fn set_state(&mut self, key: QuestionKey, state: S::State) {
+        self.history
+            .set_state(key, self.scheduler.save_state(&state));
+        self.states.insert(key, state);
+    }
+
+    /// Every review of `question`, oldest first.
+    pub fn history(&self, question: &Question<'_>) -> &[Review] {
+        self.history.reviews(question.key())
+    }
+
+    /// Every review of every question along with its scheduler state, for
+    /// saving as a progress file and loading with [`Schedule::from_progress`].
+    pub fn full_history(&self) -> &History {
+        &self.history
+    }
+
+    /// The review state of `question`, or `None` if it has never been reviewed.
+    pub fn state(&self, question: &Question<'_>) -> Option<&S::State> {
+        self.states.get(&question.key())
+    }
+
+    /// The questions from `questions` that are due now.  See
+    /// [`Schedule::due_at`].
+    pub fn due<'a>(&self, questions: impl IntoIterator<Item = Question<'a>>) -> Vec<Question<'a>> {
+        self.due_at(questions, self.clock.now())
+    }
+
+    /// The questions from `questions` that are due at `at`, most overdue first.
+    ///
+    /// Questions that have never been reviewed are always due and come after
+    /// the rest in the order given.
+    pub fn due_at<'a>(
+        &self,
+        questions: impl IntoIterator<Item = Question<'a>>,
+        at: Timestamp,
+    ) -> Vec<Question<'a>> {
+        let mut due = questions
+            .into_iter()
+            .map(|question| {
+                let due = self.state(&question).map(|state| self.scheduler.due(state));
+                (due, question)
+            })
+            .filter(|(due, _)| due.is_none_or(|due| due <= at))
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(due, _)| (due.is_none(), *due));
+        due.into_iter().map(|(_, question)| question).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::{card::Set, question::Conditions};
+
+    use super::*;
+
+    #[test]
+    fn due_questions() {
+        let set = Set::example_recall_default();
+        let conditions = Conditions {
+            include_card_back: true,
+            ..Conditions::INCLUDE_NONE
+        };
+        let now = Cell::new(Timestamp::from_unix_secs(0));
+        let mut schedule = Schedule::new(Sm2, || now.get());
+
+        let mut questions = set.questions(&conditions);
+        let first = questions.next().unwrap();
+        let second = questions.next().unwrap();
+        schedule.review(&second, Grade::Good);
+        now.set(Timestamp::from_unix_secs(60));
+        schedule.review(&first, Grade::Good);
+        assert_eq!(schedule.due(set.questions(&conditions)).len(), 4);
+
+        // Both are due tomorrow, the one reviewed first is more overdue.
+        now.set(Timestamp::from_unix_secs(2 * DAY));
+        let due = schedule.due(set.questions(&conditions));
+        assert_eq!(due.len(), 6);
+        assert_eq!(due[0], second);
+        assert_eq!(due[1], first);
+        assert_eq!(
+            schedule.state(&first).unwrap().due,
+            Timestamp::from_unix_secs(60 + DAY)
+        );
+    }
+
+    #[test]
+    fn change_scheduler() {
+        let set = Set::example_recall_default();
+        let now = Cell::new(Timestamp::from_unix_secs(0));
+        let mut schedule = Schedule::new(Sm2, || now.get());
+        let question = set.questions(Conditions::INCLUDE_ALL).next().unwrap();
+        schedule.review(&question, Grade::Good);
+        now.set(Timestamp::from_unix_secs(DAY));
+        schedule.review(&question, Grade::Good);
+        assert_eq!(schedule.state(&question).unwrap().interval_days, 6);
+
+        let schedule = schedule.with_scheduler(Fsrs::default());
+        assert_eq!(schedule.history(&question).len(), 2);
+        let state = schedule.state(&question).unwrap();
+        assert_eq!(state.last_review, Timestamp::from_unix_secs(DAY));
+        assert!(state.stability > Fsrs::default().parameters[2]);
+    }
+
+    #[test]
+    fn progress_keeps_state() {
+        let set = Set::example_recall_default();
+        let question = set.questions(Conditions::INCLUDE_ALL).next().unwrap();
+        let now = Cell::new(Timestamp::from_unix_secs(0));
+        let mut schedule = Schedule::new(Fsrs::default(), || now.get());
+        schedule.review(&question, Grade::Good);
+        now.set(Timestamp::from_unix_secs(3 * DAY));
+        schedule.review(&question, Grade::Good);
+        let due = schedule.state(&question).unwrap().due;
+
+        let mut buf = Vec::new();
+        schedule.full_history().save_to_writer(&mut buf).unwrap();
+        let (history, _) = History::load_from_reader(buf.as_slice()).unwrap();
+
+        // New settings don't move reviews already scheduled.
+        let relaxed = Fsrs {
+            desired_retention: 0.7,
+            ..Fsrs::default()
+        };
+        let loaded = Schedule::from_progress(relaxed.clone(), || now.get(), history.clone());
+        assert_eq!(loaded.state(&question), schedule.state(&question));
+        assert_eq!(loaded.history(&question), schedule.history(&question));
+        let replayed = Schedule::from_history(relaxed, || now.get(), history.clone());
+        assert!(replayed.state(&question).unwrap().due > due);
+
+        // States from another scheduler are rebuilt.
+        let sm2 = Schedule::from_progress(Sm2, || now.get(), history);
+        assert_eq!(sm2.state(&question).unwrap().repetitions, 2);
+        assert_eq!(sm2.full_history().scheduler(), Some("sm2"));
+    }
+
+    #[test]
+    fn grade_from_verdict() {
+        let mut set = Set::example_recall_default();
+        set.recall_back.check_accents = false;
+        set.flashcards[0].back = "niño".into();
+        let question = set
+            .questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .next()
+            .unwrap();
+        let grade = |answer| Grade::from_verdict(&question.check_answer(answer));
+        assert_eq!(grade("niño"), Grade::Good);
+        assert_eq!(grade("nino"), Grade::Hard);
+        assert_eq!(grade("nina"), Grade::Again);
+    }
+
+    #[test]
+    fn review_answer() {
+        let set = Set::example_recall_default();
+        let mut schedule = Schedule::new(Sm2, || Timestamp::from_unix_secs(0));
+        let question = set.questions(Conditions::INCLUDE_ALL).next().unwrap();
+        schedule.review_answer(
+            &question,
+            RecallType::Text,
+            &question.check_answer("wrong"),
+            None,
+        );
+        assert_eq!(
+            schedule.history(&question),
+            [Review {
+                at: Timestamp::from_unix_secs(0),
+                grade: Grade::Again,
+                response_time: None,
+                verdict: Some(VerdictKind::Incorrect),
+                recall: Some(RecallType::Text),
+            }]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use super::{Grade, Review, Scheduler, Timestamp};
+
+/// How quickly recall probability falls off over time.
+const DECAY: f64 = -0.5;
+/// Chosen so recall probability is 90% after one stability's worth of days.
+const FACTOR: f64 = 19.0 / 81.0;
+
+/// The Free Spaced Repetition Scheduler (FSRS-5).
+///
+/// Models each question's stability, the days until the chance of
+/// remembering it falls to 90%, and difficulty, from 1 to 10, and schedules
+/// reviews for when the chance of remembering falls to
+/// [`Fsrs::desired_retention`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fsrs {
+    /// Model weights, usually fitted to a player's review history.
+    pub parameters: [f64; 19],
+    /// Chance of remembering a question, from 0 to 1, when it is due.  Higher
+    /// values mean more frequent reviews.
+    pub desired_retention: f64,
+    /// Longest time between reviews.
+    pub maximum_interval_days: u32,
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Self {
+            parameters: [
+                0.40255, 1.18385, 3.173, 15.69105, 7.1949, 0.5345, 1.4604, 0.0046, 1.54575, 0.1192,
+                1.01925, 1.9395, 0.11, 0.29605, 2.2698, 0.2315, 2.9898, 0.51655, 0.6621,
+            ],
+            desired_retention: 0.9,
+            maximum_interval_days: 36500,
+        }
+    }
+}
+
+/// How well a question is remembered according to [`Fsrs`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FsrsState {
+    /// Days until the chance of remembering falls to 90%.
+    pub stability: f64,
+    /// How hard the question is, from 1 to 10.
+    pub difficulty: f64,
+    /// When the question was last reviewed.
+    pub last_review: Timestamp,
+    /// When the question should next be reviewed.
+    pub due: Timestamp,
+}
+
+impl Scheduler for Fsrs {
+    type State = FsrsState;
+
+    const NAME: &'static str = "fsrs";
+
+    fn review(&self, previous: Option<&FsrsState>, review: Review) -> FsrsState {
+        let w = &self.parameters;
+        let (stability, difficulty) = match previous {
+            None => (
+                self.initial_stability(review.grade),
+                self.initial_difficulty(review.grade),
+            ),
+            Some(state) => {
+                let elapsed = review.at.days_since(state.last_review);
+                let stability = if elapsed < 1.0 {
+                    self.same_day_stability(state.stability, review.grade)
+                } else {
+                    let retrievability = retrievability(elapsed, state.stability);
+                    if review.grade == Grade::Again {
+                        self.forget_stability(state, retrievability)
+                    } else {
+                        self.recall_stability(state, retrievability, review.grade)
+                    }
+                };
+                let delta = -w[6] * (grade_value(review.grade) - 3.0);
+                let difficulty = state.difficulty + delta * (10.0 - state.difficulty) / 9.0;
+                // Mean reversion towards the difficulty of an easy first answer.
+                let difficulty =
+                    w[7] * self.initial_difficulty(Grade::Easy) + (1.0 - w[7]) * difficulty;
+                (stability, difficulty.clamp(1.0, 10.0))
+            }
+        };
+
+        FsrsState {
+            stability,
+            difficulty,
+            last_review: review.at,
+            due: review.at.add_days(self.interval_days(stability)),
+        }
+    }
+
+    fn due(&self, state: &FsrsState) -> Timestamp {
+        state.due
+    }
+
+    fn save_state(&self, state: &FsrsState) -> String {
+        format!(
+            "{} {} {} {}",
+            state.stability,
+            state.difficulty,
+            state.last_review.unix_secs(),
+            state.due.unix_secs()
+        )
+    }
+
+    fn load_state(&self, s: &str) -> Option<FsrsState> {
+        let mut parts = s.split_whitespace();
+        let state = FsrsState {
+            stability: parts.next()?.parse().ok()?,
+            difficulty: parts.next()?.parse().ok()?,
+            last_review: Timestamp::from_unix_secs(parts.next()?.parse().ok()?),
+            due: Timestamp::from_unix_secs(parts.next()?.parse().ok()?),
+        };
+        parts.next().is_none().then_some(state)
+    }
+}
+
+impl Fsrs {
+    fn initial_stability(&self, grade: Grade) -> f64 {
+        self.parameters[grade as usize].max(0.1)
+    }
+
+    fn initial_difficulty(&self, grade: Grade) -> f64 {
+        let w = &self.parameters;
+        (w[4] - (w[5] * (grade_value(grade) - 1.0)).exp() + 1.0).clamp(1.0, 10.0)
+    }
+
+    fn recall_stability(&self, state: &FsrsState, retrievability: f64, grade: Grade) -> f64 {
+        let w = &self.parameters;
+        let hard_penalty = if grade == Grade::Hard { w[15] } else { 1.0 };
+        let easy_bonus = if grade == Grade::Easy { w[16] } else { 1.0 };
+        state.stability
+            * (w[8].exp()
+                * (11.0 - state.difficulty)
+                * state.stability.powf(-w[9])
+                * ((w[10] * (1.0 - retrievability)).exp() - 1.0)
+                * hard_penalty
+                * easy_bonus
+                + 1.0)
+    }
+
+    fn forget_stability(&self, state: &FsrsState, retrievability: f64) -> f64 {
+        let w = &self.parameters;
+        let stability = w[11]
+            * state.difficulty.powf(-w[12])
+            * ((state.stability + 1.0).powf(w[13]) - 1.0)
+            * (w[14] * (1.0 - retrievability)).exp();
+        stability.min(state.stability)
+    }
+
+    fn same_day_stability(&self, stability: f64, grade: Grade) -> f64 {
+        let w = &self.parameters;
+        stability * (w[17] * (grade_value(grade) - 3.0 + w[18])).exp()
+    }
+
+    /// Days until the chance of remembering a question with `stability` falls
+    /// to the desired retention.
+    fn interval_days(&self, stability: f64) -> u32 {
+        let interval = stability / FACTOR * (self.desired_retention.powf(1.0 / DECAY) - 1.0);
+        (interval.round() as u32).clamp(1, self.maximum_interval_days.max(1))
+    }
+}
+
+/// Chance of remembering a question with `stability` after `elapsed` days.
+fn retrievability(elapsed: f64, stability: f64) -> f64 {
+    (1.0 + FACTOR * elapsed / stability).powf(DECAY)
+}
+
+/// The FSRS rating, from 1 to 4, of `grade`.
+fn grade_value(grade: Grade) -> f64 {
+    f64::from(grade as u8 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_day(day: u32) -> Timestamp {
+        Timestamp::from_unix_secs(0).add_days(day)
+    }
+
+    fn first_review(fsrs: &Fsrs, grade: Grade) -> FsrsState {
+        fsrs.review(
+            None,
+            Review {
+                at: at_day(0),
+                grade,
+                response_time: None,
+                verdict: None,
+                recall: None,
+            },
+        )
+    }
+
+    #[test]
+    fn fsrs_first_review() {
+        let fsrs = Fsrs::default();
+        assert_eq!(first_review(&fsrs, Grade::Again).due, at_day(1));
+        assert_eq!(first_review(&fsrs, Grade::Good).due, at_day(3));
+        assert_eq!(first_review(&fsrs, Grade::Easy).due, at_day(16));
+        assert!(
+            first_review(&fsrs, Grade::Hard).difficulty
+                > first_review(&fsrs, Grade::Easy).difficulty
+        );
+    }
+
+    #[test]
+    fn fsrs_intervals_grow() {
+        let fsrs = Fsrs::default();
+        let mut state = first_review(&fsrs, Grade::Good);
+        let mut interval = 0;
+        for _ in 0..5 {
+            let at = state.due;
+            state = fsrs.review(
+                Some(&state),
+                Review {
+                    at,
+                    grade: Grade::Good,
+                    response_time: None,
+                    verdict: None,
+                    recall: None,
+                },
+            );
+            let next = (state.due - at).as_secs();
+            assert!(next > interval);
+            interval = next;
+        }
+
+        let forgotten = fsrs.review(
+            Some(&state),
+            Review {
+                at: state.due,
+                grade: Grade::Again,
+                response_time: None,
+                verdict: None,
+                recall: None,
+            },
+        );
+        assert!(forgotten.stability < state.stability);
+        assert!(forgotten.difficulty > state.difficulty);
+    }
+
+    #[test]
+    fn fsrs_desired_retention() {
+        let strict = Fsrs {
+            desired_retention: 0.95,
+            ..Default::default()
+        };
+        let relaxed = Fsrs {
+            desired_retention: 0.8,
+            ..Default::default()
+        };
+        assert!(first_review(&strict, Grade::Easy).due < first_review(&relaxed, Grade::Easy).due);
+
+        let capped = Fsrs {
+            maximum_interval_days: 2,
+            ..Default::default()
+        };
+        assert_eq!(first_review(&capped, Grade::Easy).due, at_day(2));
+    }
+}
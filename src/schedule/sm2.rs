@@ -0,0 +1,144 @@
+use super::{Grade, Review, Scheduler, Timestamp};
+
+/// Ease of questions that have never been reviewed.
+const INITIAL_EASE: f64 = 2.5;
+/// Ease never drops below this, so hard questions still get longer intervals.
+const MIN_EASE: f64 = 1.3;
+
+/// The SuperMemo 2 algorithm: intervals of 1 day, then 6 days, then growing
+/// by each question's ease, which drops when answers are hard.  Forgotten
+/// questions start over from 1 day without changing their ease.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sm2;
+
+impl Scheduler for Sm2 {
+    type State = Sm2State;
+
+    const NAME: &'static str = "sm2";
+
+    fn review(&self, previous: Option<&Sm2State>, review: Review) -> Sm2State {
+        Sm2State::review(previous, review.grade, review.at)
+    }
+
+    fn due(&self, state: &Sm2State) -> Timestamp {
+        state.due
+    }
+
+    fn save_state(&self, state: &Sm2State) -> String {
+        format!(
+            "{} {} {} {}",
+            state.repetitions,
+            state.interval_days,
+            state.ease,
+            state.due.unix_secs()
+        )
+    }
+
+    fn load_state(&self, s: &str) -> Option<Sm2State> {
+        let mut parts = s.split_whitespace();
+        let state = Sm2State {
+            repetitions: parts.next()?.parse().ok()?,
+            interval_days: parts.next()?.parse().ok()?,
+            ease: parts.next()?.parse().ok()?,
+            due: Timestamp::from_unix_secs(parts.next()?.parse().ok()?),
+        };
+        parts.next().is_none().then_some(state)
+    }
+}
+
+/// How well a question is remembered according to [`Sm2`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sm2State {
+    /// Reviews in a row that were not [`Grade::Again`].
+    pub repetitions: u32,
+    /// Days between the last review and the next.
+    pub interval_days: u32,
+    /// How much the interval grows after each successful review.
+    pub ease: f64,
+    /// When the question should next be reviewed.
+    pub due: Timestamp,
+}
+
+impl Sm2State {
+    /// The state after reviewing a question in `previous` state at `at`, or for
+    /// the first time if `previous` is `None`.
+    fn review(previous: Option<&Self>, grade: Grade, at: Timestamp) -> Self {
+        let (repetitions, interval_days, ease) = previous.map_or((0, 0, INITIAL_EASE), |state| {
+            (state.repetitions, state.interval_days, state.ease)
+        });
+
+        let (repetitions, interval_days, ease) = if grade == Grade::Again {
+            (0, 1, ease)
+        } else {
+            let quality = grade.quality();
+            let ease =
+                (ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(MIN_EASE);
+            let interval_days = match repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (f64::from(interval_days) * ease).round() as u32,
+            };
+            (repetitions + 1, interval_days, ease)
+        };
+
+        Self {
+            repetitions,
+            interval_days,
+            ease,
+            due: at.add_days(interval_days),
+        }
+    }
+}
+
+impl Grade {
+    /// The SM-2 response quality, from 0 to 5, of this grade.  Only used for
+    /// grades other than [`Grade::Again`].
+    fn quality(self) -> f64 {
+        match self {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reviews(grades: &[Grade]) -> Sm2State {
+        grades
+            .iter()
+            .fold(None, |state, &grade| {
+                Some(Sm2State::review(
+                    state.as_ref(),
+                    grade,
+                    Timestamp::from_unix_secs(0),
+                ))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn sm2_intervals() {
+        use Grade::*;
+        assert_eq!(reviews(&[Good]).interval_days, 1);
+        assert_eq!(reviews(&[Good, Good]).interval_days, 6);
+        assert_eq!(reviews(&[Good, Good, Good]).interval_days, 15);
+        assert_eq!(reviews(&[Good, Good, Easy]).interval_days, 16);
+        assert!((reviews(&[Easy, Easy, Easy]).ease - 2.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sm2_forgotten() {
+        use Grade::*;
+        let state = reviews(&[Good, Good, Good, Again]);
+        assert_eq!(state.repetitions, 0);
+        assert_eq!(state.interval_days, 1);
+        assert_eq!(state.due, Timestamp::from_unix_secs(0).add_days(1));
+        assert_eq!(state.ease, reviews(&[Good, Good, Good]).ease);
+        assert_eq!(reviews(&[Again; 10]).ease, INITIAL_EASE);
+        assert!(reviews(&[Hard; 10]).ease >= MIN_EASE);
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::VecDeque;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    card::RecallType,
+    question::{AnswerVerdict, McList, Question},
+};
+
+/// Asks questions in a random order until each has been answered correctly,
+/// asking missed questions again a few questions later.
+///
+/// In learn mode ([`SessionSettings::learn_mode`]) questions instead have to
+/// be answered correctly several times at each of a series of stages, such as
+/// multiple choice with a few choices, then more choices, then typing.
+#[derive(Debug)]
+pub struct Session<'a, R> {
+    settings: SessionSettings,
+    rng: R,
+    queue: VecDeque<Entry<'a>>,
+    learned: usize,
+}
+
+/// A question waiting to be asked and how far through learn mode it is.
+#[derive(Debug)]
+struct Entry<'a> {
+    question: Question<'a>,
+    /// Index into [`LearnMode::stages`].
+    stage: usize,
+    /// Correct answers in a row at this stage.
+    streak: usize,
+}
+
+/// Options for a [`Session`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionSettings {
+    /// How many other questions are asked before a missed question is asked
+    /// again.
+    pub requeue_spacing: usize,
+    /// How many choices multiple choice questions have outside of learn mode.
+    pub mc_choices: usize,
+    /// How questions move from easier to harder ways of asking them, or
+    /// `None` to ask each question the way its set says to.
+    pub learn_mode: Option<LearnMode>,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            requeue_spacing: 3,
+            mc_choices: 4,
+            learn_mode: None,
+        }
+    }
+}
+
+/// Stages questions move through in learn mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LearnMode {
+    /// Ways of asking each question, easiest first.  A question is learned
+    /// once it has been answered correctly enough times at the last stage.
+    pub stages: Vec<Stage>,
+    /// Correct answers in a row needed to move a question to the next stage.
+    /// A wrong answer moves it back a stage.
+    pub answers_per_stage: usize,
+}
+
+impl Default for LearnMode {
+    fn default() -> Self {
+        Self {
+            stages: vec![
+                Stage::Mc { choices: 3 },
+                Stage::Mc { choices: 6 },
+                Stage::Text,
+            ],
+            answers_per_stage: 2,
+        }
+    }
+}
+
+/// How a question is asked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Pick the answer from a list of this many choices.
+    Mc { choices: usize },
+    /// Type in the answer.
+    Text,
+}
+
+impl Stage {
+    /// The [`RecallType`] of questions asked at this stage, for
+    /// [`crate::schedule::Schedule::review_answer`].
+    pub fn recall_type(self) -> RecallType {
+        match self {
+            Stage::Mc { .. } => RecallType::Mc,
+            Stage::Text => RecallType::Text,
+        }
+    }
+}
+
+/// How far through a [`Session`] the player is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Questions not yet answered correctly.
+    pub remaining: usize,
+    /// Questions answered correctly.
+    pub learned: usize,
+}
+
+impl Progress {
+    /// Number of questions in the session.
+    pub fn total(&self) -> usize {
+        self.remaining + self.learned
+    }
+}
+
+impl<'a, R: Rng> Session<'a, R> {
+    /// A session asking `questions` in an order picked by `rng`.
+    pub fn new(
+        questions: impl IntoIterator<Item = Question<'a>>,
+        settings: SessionSettings,
+        mut rng: R,
+    ) -> Self {
+        let mut questions = questions.into_iter().collect::<Vec<_>>();
+        questions.shuffle(&mut rng);
+        let queue = questions
+            .into_iter()
+            .map(|question| Entry {
+                question,
+                stage: 0,
+                streak: 0,
+            })
+            .collect();
+        Self {
+            settings,
+            rng,
+            queue,
+            learned: 0,
+        }
+    }
+
+    /// The question to ask, or `None` once every question has been answered
+    /// correctly.
+    ///
+    /// Stays the same until an answer is submitted.
+    pub fn next_question(&self) -> Option<&Question<'a>> {
+        self.queue.front().map(|entry| &entry.question)
+    }
+
+    /// How the question returned by [`Session::next_question`] should be
+    /// asked.
+    ///
+    /// In learn mode this is the stage the question is at, otherwise it
+    /// depends on the [`RecallType`] of the question's set.
+    pub fn stage(&self) -> Option<Stage> {
+        let entry = self.queue.front()?;
+        match &self.settings.learn_mode {
+            Some(learn_mode) if !learn_mode.stages.is_empty() => {
+                Some(learn_mode.stages[entry.stage])
+            }
+            _ => Some(match entry.question.recall_type() {
+                RecallType::Text => Stage::Text,
+                RecallType::Mc | RecallType::None => Stage::Mc {
+                    choices: self.settings.mc_choices,
+                },
+            }),
+        }
+    }
+
+    /// Choices for the question returned by [`Session::next_question`] if it
+    /// should be asked as multiple choice, with as many choices as its
+    /// [`Stage`] says.
+    pub fn mc_answers(&mut self) -> Option<McList<'a>> {
+        let Stage::Mc { choices } = self.stage()? else {
+            return None;
+        };
+        let entry = self.queue.front()?;
+        entry.question.mc_answers(choices, &mut self.rng)
+    }
+
+    /// Checks `answer` against the question returned by
+    /// [`Session::next_question`] and moves on to the next question.
+    ///
+    /// Questions answered wrong are asked again after
+    /// [`SessionSettings::requeue_spacing`] other questions, or last if there
+    /// aren't that many left.  In learn mode, questions answered correctly but
+    /// not yet learned are asked again after all the others.  Returns `None`
+    /// if there are no questions left.
+    pub fn submit_answer(&mut self, answer: &str) -> Option<AnswerVerdict<'a>> {
+        let entry = self.queue.pop_front()?;
+        let verdict = entry.question.check_answer(answer);
+        self.requeue(entry, verdict.is_correct());
+        Some(verdict)
+    }
+
+    /// Checks the choice at `index` in `choices`, which should be from
+    /// [`Session::mc_answers`], and moves on to the next question the same
+    /// way as [`Session::submit_answer`].
+    ///
+    /// Returns whether the choice was correct, or `None` if there are no
+    /// questions left or no choice at `index`.
+    pub fn submit_choice(&mut self, choices: &McList<'a>, index: usize) -> Option<bool> {
+        let correct = choices.select(index)?;
+        let entry = self.queue.pop_front()?;
+        self.requeue(entry, correct);
+        Some(correct)
+    }
+
+    /// Moves `entry` back into the queue, or counts it as learned, after it
+    /// was answered.
+    fn requeue(&mut self, mut entry: Entry<'a>, correct: bool) {
+        let (last_stage, answers_per_stage) = match &self.settings.learn_mode {
+            Some(learn_mode) if !learn_mode.stages.is_empty() => {
+                (learn_mode.stages.len() - 1, learn_mode.answers_per_stage)
+            }
+            _ => (0, 1),
+        };
+
+        if correct {
+            entry.streak += 1;
+            if entry.streak < answers_per_stage {
+                self.queue.push_back(entry);
+            } else if entry.stage < last_stage {
+                entry.stage += 1;
+                entry.streak = 0;
+                self.queue.push_back(entry);
+            } else {
+                self.learned += 1;
+            }
+        } else {
+            entry.stage = entry.stage.saturating_sub(1);
+            entry.streak = 0;
+            let index = self.settings.requeue_spacing.min(self.queue.len());
+            self.queue.insert(index, entry);
+        }
+    }
+
+    /// How many questions are left and how many have been answered correctly.
+    pub fn progress(&self) -> Progress {
+        Progress {
+            remaining: self.queue.len(),
+            learned: self.learned,
+        }
+    }
+
+    /// The random number generator used by this session, for picking the text
+    /// of questions and multiple choice answers.
+    pub fn rng(&mut self) -> &mut R {
+        &mut self.rng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{card::Set, question::Conditions};
+
+    use super::*;
+
+    fn session(set: &Set, requeue_spacing: usize) -> Session<'_, ChaCha8Rng> {
+        Session::new(
+            set.questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            }),
+            SessionSettings {
+                requeue_spacing,
+                ..Default::default()
+            },
+            ChaCha8Rng::from_seed(Default::default()),
+        )
+    }
+
+    fn correct_answer<R: Rng>(session: &mut Session<'_, R>) -> String {
+        let question = &session.queue.front().unwrap().question;
+        let answers = question.mc_answers(2, &mut session.rng).unwrap();
+        answers.correct().text().to_owned()
+    }
+
+    #[test]
+    fn session_all_correct() {
+        let set = Set::example_recall_default();
+        let mut session = session(&set, 3);
+        assert_eq!(
+            session.progress(),
+            Progress {
+                remaining: 6,
+                learned: 0
+            }
+        );
+        while session.next_question().is_some() {
+            let answer = correct_answer(&mut session);
+            assert!(session.submit_answer(&answer).unwrap().is_correct());
+        }
+        assert_eq!(
+            session.progress(),
+            Progress {
+                remaining: 0,
+                learned: 6
+            }
+        );
+        assert_eq!(session.progress().total(), 6);
+        assert_eq!(session.submit_answer("0"), None);
+    }
+
+    #[test]
+    fn session_submit_choice() {
+        let mut set = Set::example_recall_default();
+        set.recall_mc.none_of_the_above = 100;
+        let mut session = Session::new(
+            set.questions(Conditions {
+                include_mc: true,
+                ..Conditions::INCLUDE_NONE
+            }),
+            SessionSettings::default(),
+            ChaCha8Rng::from_seed(Default::default()),
+        );
+        let choices = session.mc_answers().unwrap();
+        assert_eq!(choices.choice_count(), 4);
+        assert_eq!(session.submit_choice(&choices, 4), None);
+        let wrong = (choices.correct_index() + 1) % 4;
+        assert_eq!(session.submit_choice(&choices, wrong), Some(false));
+
+        while session.next_question().is_some() {
+            let choices = session.mc_answers().unwrap();
+            assert_eq!(
+                session.submit_choice(&choices, choices.correct_index()),
+                Some(true)
+            );
+        }
+        assert_eq!(session.progress().learned, 4);
+    }
+
+    #[test]
+    fn session_requeue_miss() {
+        let set = Set::example_recall_default();
+        let mut session = session(&set, 2);
+        let missed = session.next_question().unwrap().key();
+        assert!(!session.submit_answer("wrong").unwrap().is_correct());
+        assert_eq!(session.progress().remaining, 6);
+
+        for _ in 0..2 {
+            assert_ne!(session.next_question().unwrap().key(), missed);
+            let answer = correct_answer(&mut session);
+            session.submit_answer(&answer);
+        }
+        assert_eq!(session.next_question().unwrap().key(), missed);
+    }
+
+    #[test]
+    fn session_requeue_last() {
+        let set = Set::example_recall_default();
+        let mut session = session(&set, 100);
+        let missed = session.next_question().unwrap().key();
+        session.submit_answer("wrong");
+        assert_eq!(session.queue.back().unwrap().question.key(), missed);
+    }
+
+    #[test]
+    fn session_stage_from_set() {
+        let mut set = Set::example_recall_default();
+        let mut mc = session(&set, 3);
+        assert_eq!(mc.stage(), Some(Stage::Mc { choices: 4 }));
+        assert_eq!(mc.mc_answers().unwrap().choice_count(), 4);
+
+        set.recall_back.typ = RecallType::Text;
+        let mut text = session(&set, 3);
+        assert_eq!(text.stage(), Some(Stage::Text));
+        assert!(text.mc_answers().is_none());
+    }
+
+    #[test]
+    fn learn_mode_stages() {
+        let set = Set::example_recall_default();
+        let mut session = Session::new(
+            set.questions(Conditions {
+                include_card_back: true,
+                ..Conditions::INCLUDE_NONE
+            })
+            .take(1),
+            SessionSettings {
+                learn_mode: Some(LearnMode::default()),
+                ..Default::default()
+            },
+            ChaCha8Rng::from_seed(Default::default()),
+        );
+        let answer = correct_answer(&mut session);
+
+        assert_eq!(session.stage(), Some(Stage::Mc { choices: 3 }));
+        assert_eq!(session.mc_answers().unwrap().choice_count(), 3);
+        session.submit_answer(&answer);
+        assert_eq!(session.stage(), Some(Stage::Mc { choices: 3 }));
+        session.submit_answer(&answer);
+        assert_eq!(session.stage(), Some(Stage::Mc { choices: 6 }));
+        assert_eq!(session.mc_answers().unwrap().choice_count(), 6);
+
+        // A miss moves the question back a stage.
+        session.submit_answer("wrong");
+        assert_eq!(session.stage(), Some(Stage::Mc { choices: 3 }));
+
+        for stage in [
+            Stage::Mc { choices: 3 },
+            Stage::Mc { choices: 3 },
+            Stage::Mc { choices: 6 },
+            Stage::Mc { choices: 6 },
+            Stage::Text,
+        ] {
+            assert_eq!(session.stage(), Some(stage));
+            session.submit_answer(&answer);
+            assert_eq!(session.progress().learned, 0);
+        }
+        assert_eq!(session.stage(), Some(Stage::Text));
+        assert!(session.mc_answers().is_none());
+        session.submit_answer(&answer);
+        assert_eq!(
+            session.progress(),
+            Progress {
+                remaining: 0,
+                learned: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use std::{collections::HashSet, time::Duration};
+
+use crate::{
+    card::{CardId, Flashcard, McCard, RecallType, Set, Side},
+    progress::History,
+    question::QuestionKey,
+    schedule::{Grade, Review},
+};
+
+/// How many answers were correct.  Answers graded [`Grade::Again`] are wrong;
+/// all others are correct.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Accuracy {
+    /// Answers that were correct.
+    pub correct: usize,
+    /// All answers.
+    pub total: usize,
+}
+
+impl Accuracy {
+    /// Answers that were wrong.
+    pub fn missed(&self) -> usize {
+        self.total - self.correct
+    }
+
+    /// Fraction of answers that were correct, or `None` if there were none.
+    pub fn ratio(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.correct as f64 / self.total as f64)
+    }
+
+    fn add(&mut self, review: &Review) {
+        self.total += 1;
+        if review.grade != Grade::Again {
+            self.correct += 1;
+        }
+    }
+}
+
+/// Runs of correct answers in a row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Correct answers since the last wrong one.
+    pub current: usize,
+    /// Most correct answers in a row.
+    pub longest: usize,
+}
+
+impl Streaks {
+    /// Streaks in `reviews`, which must be in the order they happened.
+    fn new<'r>(reviews: impl IntoIterator<Item = &'r Review>) -> Self {
+        let mut streaks = Self::default();
+        for review in reviews {
+            if review.grade == Grade::Again {
+                streaks.current = 0;
+            } else {
+                streaks.current += 1;
+                streaks.longest = streaks.longest.max(streaks.current);
+            }
+        }
+        streaks
+    }
+}
+
+/// A card in a [`Set`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CardRef<'a> {
+    Flashcard(&'a Flashcard),
+    Mc(&'a McCard),
+}
+
+impl<'a> CardRef<'a> {
+    /// The id of the card.
+    pub fn id(&self) -> CardId {
+        match self {
+            CardRef::Flashcard(card) => card.id(),
+            CardRef::Mc(card) => card.id(),
+        }
+    }
+}
+
+/// Statistics about the answers to the questions from one card.
+///
+/// Created by [`Set::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardStats<'a> {
+    pub card: CardRef<'a>,
+    /// Accuracy of all answers to questions from this card.
+    pub accuracy: Accuracy,
+    /// Accuracy of answers recalling the front of this flashcard.  Empty for
+    /// multiple choice cards.
+    pub front: Accuracy,
+    /// Accuracy of answers recalling the back of this flashcard.  Empty for
+    /// multiple choice cards.
+    pub back: Accuracy,
+    pub streaks: Streaks,
+    /// Average time taken to answer, counting only answers that were timed.
+    pub average_response_time: Option<Duration>,
+}
+
+/// Statistics about the answers to the questions in a set.
+///
+/// Created by [`Set::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetStats<'a> {
+    /// Accuracy of all answers.
+    pub accuracy: Accuracy,
+    /// Accuracy of answers recalling the front of flashcards.
+    pub front: Accuracy,
+    /// Accuracy of answers recalling the back of flashcards.
+    pub back: Accuracy,
+    /// Accuracy of answers to multiple choice cards.
+    pub mc: Accuracy,
+    /// Accuracy of answers to questions asked with each [`RecallType`].
+    /// Reviews that don't record how the question was asked count under the
+    /// type currently set in the set.
+    pub by_recall_type: Vec<(RecallType, Accuracy)>,
+    pub streaks: Streaks,
+    /// Average time taken to answer, counting only answers that were timed.
+    pub average_response_time: Option<Duration>,
+    /// Statistics for each card, in the order they are in the set.
+    pub cards: Vec<CardStats<'a>>,
+}
+
+impl<'a> SetStats<'a> {
+    /// Up to `count` cards with at least one wrong answer, most wrong answers
+    /// first.
+    pub fn most_missed(&self, count: usize) -> Vec<&CardStats<'a>> {
+        let mut cards = self
+            .cards
+            .iter()
+            .filter(|card| card.accuracy.missed() > 0)
+            .collect::<Vec<_>>();
+        cards.sort_by_key(|card| std::cmp::Reverse(card.accuracy.missed()));
+        cards.truncate(count);
+        cards
+    }
+}
+
+impl Set {
+    /// Statistics about the answers in `history` to questions from this set.
+    ///
+    /// Answers to cards that aren't in this set are ignored.  Cards with the
+    /// same [`CardId`], which [`Set::load`] rejects, share their answers;
+    /// they are only counted for the first of them.
+    pub fn stats(&self, history: &History) -> SetStats<'_> {
+        let mut stats = SetStats {
+            accuracy: Accuracy::default(),
+            front: Accuracy::default(),
+            back: Accuracy::default(),
+            mc: Accuracy::default(),
+            by_recall_type: Vec::new(),
+            streaks: Streaks::default(),
+            average_response_time: None,
+            cards: Vec::new(),
+        };
+        let mut all_reviews = Vec::<&Review>::new();
+        let mut counted = HashSet::new();
+
+        let cards = self
+            .flashcards
+            .iter()
+            .map(CardRef::Flashcard)
+            .chain(self.mc_cards.iter().map(CardRef::Mc));
+        for card in cards {
+            let mut card_stats = CardStats {
+                card,
+                accuracy: Accuracy::default(),
+                front: Accuracy::default(),
+                back: Accuracy::default(),
+                streaks: Streaks::default(),
+                average_response_time: None,
+            };
+            let sides: &[_] = match card {
+                CardRef::Flashcard(_) => &[Some(Side::Front), Some(Side::Back)],
+                CardRef::Mc(_) => &[None],
+            };
+            let mut card_reviews = Vec::<&Review>::new();
+            for &side in sides {
+                let key = QuestionKey {
+                    card: card.id(),
+                    side,
+                };
+                let reviews = if counted.insert(key) {
+                    history.reviews(key)
+                } else {
+                    &[]
+                };
+                let (side_accuracy, set_side_accuracy, typ) = match side {
+                    Some(Side::Front) => (
+                        &mut card_stats.front,
+                        &mut stats.front,
+                        self.recall_front.typ,
+                    ),
+                    Some(Side::Back) => {
+                        (&mut card_stats.back, &mut stats.back, self.recall_back.typ)
+                    }
+                    None => (&mut Accuracy::default(), &mut stats.mc, self.recall_mc.typ),
+                };
+                for review in reviews {
+                    side_accuracy.add(review);
+                    set_side_accuracy.add(review);
+                    recall_accuracy(&mut stats.by_recall_type, review.recall.unwrap_or(typ))
+                        .add(review);
+                    card_stats.accuracy.add(review);
+                    stats.accuracy.add(review);
+                }
+                card_reviews.extend(reviews);
+            }
+
+            card_reviews.sort_by_key(|review| review.at);
+            card_stats.streaks = Streaks::new(card_reviews.iter().copied());
+            card_stats.average_response_time = average_response_time(&card_reviews);
+            all_reviews.extend(card_reviews);
+            stats.cards.push(card_stats);
+        }
+
+        all_reviews.sort_by_key(|review| review.at);
+        stats.streaks = Streaks::new(all_reviews.iter().copied());
+        stats.average_response_time = average_response_time(&all_reviews);
+        stats
+    }
+}
+
+/// The accuracy for `typ` in `by_recall_type`, added if it isn't there yet.
+fn recall_accuracy(
+    by_recall_type: &mut Vec<(RecallType, Accuracy)>,
+    typ: RecallType,
+) -> &mut Accuracy {
+    match by_recall_type.iter().position(|(other, _)| *other == typ) {
+        Some(index) => &mut by_recall_type[index].1,
+        None => {
+            by_recall_type.push((typ, Accuracy::default()));
+            &mut by_recall_type.last_mut().unwrap().1
+        }
+    }
+}
+
+fn average_response_time(reviews: &[&Review]) -> Option<Duration> {
+    let times = reviews
+        .iter()
+        .filter_map(|review| review.response_time)
+        .collect::<Vec<_>>();
+    let count = u32::try_from(times.len()).ok().filter(|&count| count > 0)?;
+    Some(times.iter().sum::<Duration>() / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schedule::Timestamp;
+
+    use super::*;
+
+    fn review(at: u64, grade: Grade, response_secs: Option<u64>) -> Review {
+        Review {
+            at: Timestamp::from_unix_secs(at),
+            grade,
+            response_time: response_secs.map(Duration::from_secs),
+            verdict: None,
+            recall: None,
+        }
+    }
+
+    fn example_history(set: &Set) -> History {
+        let back = |index: usize| QuestionKey {
+            card: set.flashcards[index].id(),
+            side: Some(Side::Back),
+        };
+        let front = |index: usize| QuestionKey {
+            card: set.flashcards[index].id(),
+            side: Some(Side::Front),
+        };
+        let mc = QuestionKey {
+            card: set.mc_cards[0].id(),
+            side: None,
+        };
+        [
+            (back(0), review(0, Grade::Good, Some(2))),
+            (front(0), review(1, Grade::Again, Some(6))),
+            (back(1), review(2, Grade::Again, None)),
+            (back(1), review(3, Grade::Again, None)),
+            (mc, review(4, Grade::Easy, None)),
+            (back(0), review(5, Grade::Hard, Some(4))),
+            (back(1), review(6, Grade::Good, None)),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn set_stats() {
+        let mut set = Set::example_recall_default();
+        set.recall_back.typ = RecallType::Text;
+        let stats = set.stats(&example_history(&set));
+
+        assert_eq!(
+            stats.accuracy,
+            Accuracy {
+                correct: 4,
+                total: 7
+            }
+        );
+        assert_eq!(
+            stats.back,
+            Accuracy {
+                correct: 3,
+                total: 5
+            }
+        );
+        assert_eq!(
+            stats.front,
+            Accuracy {
+                correct: 0,
+                total: 1
+            }
+        );
+        assert_eq!(
+            stats.mc,
+            Accuracy {
+                correct: 1,
+                total: 1
+            }
+        );
+        assert_eq!(
+            stats.by_recall_type,
+            [
+                (
+                    RecallType::Mc,
+                    Accuracy {
+                        correct: 1,
+                        total: 2
+                    }
+                ),
+                (
+                    RecallType::Text,
+                    Accuracy {
+                        correct: 3,
+                        total: 5
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            stats.streaks,
+            Streaks {
+                current: 3,
+                longest: 3
+            }
+        );
+        assert_eq!(stats.average_response_time, Some(Duration::from_secs(4)));
+        assert_eq!(stats.cards.len(), 10);
+    }
+
+    #[test]
+    fn recorded_recall_type() {
+        let mut set = Set::example_recall_default();
+        set.recall_back.typ = RecallType::Text;
+        let back = QuestionKey {
+            card: set.flashcards[0].id(),
+            side: Some(Side::Back),
+        };
+        let history = [
+            Review {
+                recall: Some(RecallType::Mc),
+                ..review(0, Grade::Good, None)
+            },
+            Review {
+                recall: Some(RecallType::Text),
+                ..review(1, Grade::Again, None)
+            },
+            review(2, Grade::Good, None),
+        ]
+        .into_iter()
+        .map(|review| (back, review))
+        .collect();
+        let stats = set.stats(&history);
+        assert_eq!(
+            stats.by_recall_type,
+            [
+                (
+                    RecallType::Mc,
+                    Accuracy {
+                        correct: 1,
+                        total: 1
+                    }
+                ),
+                (
+                    RecallType::Text,
+                    Accuracy {
+                        correct: 1,
+                        total: 2
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_card_ids() {
+        let mut set = Set::example_recall_default();
+        let mut same_front = set.flashcards[0].clone();
+        same_front.back = "other".into();
+        set.flashcards[1] = same_front;
+        let key = QuestionKey {
+            card: set.flashcards[0].id(),
+            side: Some(Side::Back),
+        };
+        assert_eq!(set.flashcards[1].id(), key.card);
+        let history = [(key, review(0, Grade::Again, None))].into_iter().collect();
+        let stats = set.stats(&history);
+        assert_eq!(stats.accuracy.total, 1);
+        assert_eq!(stats.cards[0].accuracy.total, 1);
+        assert_eq!(stats.cards[1].accuracy.total, 0);
+        assert_eq!(stats.most_missed(5).len(), 1);
+    }
+
+    #[test]
+    fn card_stats() {
+        let set = Set::example_recall_default();
+        let stats = set.stats(&example_history(&set));
+
+        let first = &stats.cards[0];
+        assert_eq!(first.card, CardRef::Flashcard(&set.flashcards[0]));
+        assert_eq!(
+            first.accuracy,
+            Accuracy {
+                correct: 2,
+                total: 3
+            }
+        );
+        assert_eq!(
+            first.front,
+            Accuracy {
+                correct: 0,
+                total: 1
+            }
+        );
+        assert_eq!(
+            first.back,
+            Accuracy {
+                correct: 2,
+                total: 2
+            }
+        );
+        assert_eq!(
+            first.streaks,
+            Streaks {
+                current: 1,
+                longest: 1
+            }
+        );
+        assert_eq!(first.average_response_time, Some(Duration::from_secs(4)));
+        assert_eq!(stats.cards[2].accuracy.ratio(), None);
+
+        let most_missed = stats.most_missed(5);
+        assert_eq!(most_missed.len(), 2);
+        assert_eq!(most_missed[0].card, CardRef::Flashcard(&set.flashcards[1]));
+        assert_eq!(most_missed[1].card, CardRef::Flashcard(&set.flashcards[0]));
+    }
+}